@@ -1,3 +1,79 @@
+//! Tests for the `#[component]` macro. Those that must fail to compile are doctests below.
+//!
+//! Calling an unkeyed hook like [state](avalanche::state) within a loop is a compile error:
+//! ```compile_fail
+//! use avalanche::{component, state, tracked, View};
+//!
+//! #[component]
+//! fn Counters() -> View {
+//!     for _ in 0..3 {
+//!         let (count, _) = state(self, || 0);
+//!     }
+//!     ().into()
+//! }
+//! ```
+//!
+//! as is calling one within an iterator closure:
+//! ```compile_fail
+//! use avalanche::{component, keyed, state, tracked, View};
+//!
+//! #[component]
+//! fn Counters() -> View {
+//!     let children = ["a", "b", "c"].iter().map(|name| {
+//!         let (count, _) = state(self, || 0);
+//!         keyed(self, name, || Counter(self, count = *tracked!(count)))
+//!     });
+//!     ().into()
+//! }
+//!
+//! #[component]
+//! fn Counter(count: u32) -> View {
+//!     ().into()
+//! }
+//! ```
+//!
+//! Keyed hooks may be called there instead:
+//! ```
+//! use avalanche::{component, keyed, state_keyed, tracked, View};
+//!
+//! #[component]
+//! fn Counters() -> View {
+//!     let children = ["a", "b", "c"].iter().map(|name| {
+//!         let (count, _) = state_keyed(self, name, || 0);
+//!         keyed(self, name, || Counter(self, count = *tracked!(count)))
+//!     });
+//!     ().into()
+//! }
+//!
+//! #[component]
+//! fn Counter(count: u32) -> View {
+//!     ().into()
+//! }
+//! ```
+//!
+//! as may unkeyed hooks given a keyed context, or called in closures that are not iterator adapters:
+//! ```
+//! use avalanche::{component, keyed, state, tracked, View};
+//!
+//! #[component]
+//! fn Counters(label: Option<String>) -> View {
+//!     let children = (0..3).map(|i| {
+//!         let (count, _) = state(self.with_key(i), || 0);
+//!         keyed(self, i, || Counter(self, count = *tracked!(count)))
+//!     });
+//!     let label_clicks = tracked!(label).as_ref().map(|label| {
+//!         let (clicks, _) = state(self, || 0);
+//!         *tracked!(clicks)
+//!     });
+//!     ().into()
+//! }
+//!
+//! #[component]
+//! fn Counter(count: u32) -> View {
+//!     ().into()
+//! }
+//! ```
+
 #![allow(
     deprecated,
     unused_variables,
@@ -1,7 +1,7 @@
 use std::fmt::Display;
 
 use avalanche::renderer::{
-    DispatchNativeEvent, NativeEvent, NativeHandle, Renderer, Scheduler,
+    DispatchNativeEvent, NativeEvent, NativeHandle, Priority, Renderer, Scheduler,
 };
 use avalanche::tracked::Gen;
 use avalanche::vdom::Root;
@@ -57,7 +57,7 @@ impl Renderer for TestRenderer {
 struct TestScheduler;
 
 impl Scheduler for TestScheduler {
-    fn schedule_on_ui_thread(&mut self, _f: Box<dyn FnOnce()>) {}
+    fn schedule_on_ui_thread(&mut self, _f: Box<dyn FnOnce()>, _priority: Priority) {}
 }
 
 struct TestChildren<'a> {
@@ -164,6 +164,7 @@ fn Test() -> View {
             Macros(self, a = tracked!(a), b = tracked!(b), c = tracked!(c)),
             NestedBlocks(self, a = tracked!(a)),
             NestedTracked(self, a = tracked!(a), b = tracked!(b)),
+            KeyedAccess(self, a = tracked!(a), b = tracked!(b)),
             Updated(self, a = tracked!(a), b = tracked!(b), c = tracked!(c)),
             BasicRef(self, a = &tracked!(a)),
             ComplexRef(self, a = &tracked!(a), bc = &[&tracked!(b), &tracked!(c)]),
@@ -544,6 +545,59 @@ fn NestedTracked(a: u8, b: u8) -> View {
     ().into()
 }
 
+struct KeyedRows {
+    rows: Vec<Tracked<String>>,
+}
+
+#[component]
+fn KeyedAccess(a: u8, b: u8) -> View {
+    let vec = Tracked::new(
+        vec![
+            Tracked::new(0u8, Gen::escape_hatch_new(false)),
+            Tracked::new(1u8, Gen::escape_hatch_new(true)),
+        ],
+        Gen::escape_hatch_new(true),
+    );
+
+    // reading an element only depends on the element, not on its container
+    let first = tracked_keyed!(vec[0]);
+    assert!(!updated!(first));
+    let second = tracked_keyed!(vec[1]);
+    assert!(updated!(second));
+    assert!(!updated_keyed!(vec[0]));
+    assert!(updated_keyed!(vec[1]));
+    assert!(updated!(vec));
+
+    // explicitly unwrapped containers behave the same way
+    let first = tracked_keyed!(tracked!(vec)[0]);
+    assert!(!updated!(first));
+
+    // index expressions are still tracked
+    let indexed = tracked_keyed!(vec[tracked!(b) as usize]);
+    assert!(!updated!(indexed));
+    let indexed = tracked_keyed!(vec[tracked!(a) as usize]);
+    assert!(updated!(indexed));
+
+    // non-Copy values are borrowed from their containers
+    let data = Tracked::new(
+        KeyedRows {
+            rows: vec![Tracked::new("row".to_owned(), Gen::escape_hatch_new(false))],
+        },
+        Gen::escape_hatch_new(true),
+    );
+    let len = tracked_keyed!(data.rows[0]).len();
+    assert!(!updated!(len));
+    let row = tracked_keyed!(data.rows[0]).clone();
+    assert!(!updated!(row));
+    assert!(tracked!(row) == "row");
+
+    // closures reading keyed values still depend on the container
+    let closure = || tracked_keyed!(vec[0]);
+    assert!(updated!(closure));
+
+    ().into()
+}
+
 #[component]
 fn Updated(a: u8, b: u8, c: u8) -> View {
     assert!(updated!(a));
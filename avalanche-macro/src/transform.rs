@@ -1,14 +1,14 @@
 use proc_macro2::{Span, TokenStream};
 use std::{collections::HashSet, hash::Hash, ops::Deref, ops::DerefMut};
 use syn::{
-    parse2, parse_quote, spanned::Spanned, token::Semi, Block, Expr, ExprPath, Ident, Lit, Pat,
-    PatType, Path, Stmt,
+    parse2, parse_quote, parse_quote_spanned, spanned::Spanned, token::Semi, Block, Expr, ExprPath,
+    Ident, Lit, Pat, PatType, Path, Stmt,
 };
 
 // Span line and column information with proc macros is not available on stable
 // To emulate unique identities for given component instantiations,
 // we currently instead generate random line and column numbers
-use proc_macro_error::abort;
+use proc_macro_error::{abort, emit_error};
 use quote::{quote_spanned, ToTokens};
 use rand::random;
 
@@ -156,6 +156,9 @@ fn enable_expr_tracking(expr: &mut Expr, deps: &UnitDeps) {
 #[derive(Default, Debug)]
 pub(crate) struct Function {
     pub(crate) scopes: Vec<Scope>,
+    /// The number of enclosing loops and iterator closures, whose bodies may be
+    /// executed more than once per render.
+    repeated_depth: usize,
 }
 
 impl Function {
@@ -244,7 +247,7 @@ impl Function {
             Stmt::Local(local) => {
                 let (stmts, mut init_dependencies, vars) = match &mut local.init {
                     Some((_, expr)) => {
-                        let deps = self.expr(expr, false);
+                        let deps = self.expr(expr);
                         let vars = vars_from_pat(&local.pat, deps.clone());
                         match &mut local.pat {
                             // for a simple let name = expr; statement, create simpler codegen
@@ -300,7 +303,7 @@ impl Function {
             Stmt::Item(item) => {
                 let deps = match item {
                     syn::Item::Macro(macro_item) => {
-                        let (mut deps, transformed) = self.mac(&mut macro_item.mac, false);
+                        let (mut deps, transformed) = self.mac(&mut macro_item.mac);
                         if let Some(transformed) = transformed {
                             stmt = Stmt::Semi(transformed, Semi(Span::call_site()));
                         }
@@ -314,11 +317,11 @@ impl Function {
                 (vec![stmt], deps)
             }
             Stmt::Expr(expr) => {
-                let deps = self.expr(expr, false);
+                let deps = self.expr(expr);
                 (vec![stmt], deps)
             }
             Stmt::Semi(expr, _) => {
-                let mut escape_expr = self.escape_expr(expr, false);
+                let mut escape_expr = self.escape_expr(expr);
                 if !escape_expr.escape {
                     enable_expr_tracking(expr, &escape_expr.dependencies);
                 }
@@ -331,13 +334,13 @@ impl Function {
     }
 
     /// Returns the dependencies of a macro, and the transformed version of the input.
-    fn mac(&mut self, mac: &mut syn::Macro, nested_tracked: bool) -> (UnitDeps, Option<Expr>) {
+    fn mac(&mut self, mac: &mut syn::Macro) -> (UnitDeps, Option<Expr>) {
         let avalanche_path = get_avalanche_path();
         let name = mac.path.segments.last().unwrap().ident.to_string();
         match &*name {
             "addr_of" | "addr_of_mut" => {
                 if let Ok(mut expr) = mac.parse_body::<Expr>() {
-                    let deps = self.expr(&mut expr, nested_tracked);
+                    let deps = self.expr(&mut expr);
                     mac.tokens = expr.into_token_stream();
                     return (deps, None);
                 }
@@ -351,9 +354,9 @@ impl Function {
                     for expr in format.exprs.iter_mut() {
                         // interpret assignment as providing named parameter
                         if let Expr::Assign(assign) = expr {
-                            unit_deps.extend(self.expr(&mut assign.right, nested_tracked));
+                            unit_deps.extend(self.expr(&mut assign.right));
                         } else {
-                            unit_deps.extend(self.expr(expr, nested_tracked));
+                            unit_deps.extend(self.expr(expr));
                         }
                     }
                     mac.tokens = format.into_token_stream();
@@ -363,13 +366,13 @@ impl Function {
             "dbg" => {
                 if let Ok(mut dbg) = mac.parse_body::<ExprList>() {
                     if dbg.exprs.len() == 1 {
-                        let deps = self.expr(&mut dbg.exprs[0], nested_tracked);
+                        let deps = self.expr(&mut dbg.exprs[0]);
                         mac.tokens = dbg.into_token_stream();
                         return (deps, None);
                     } else if dbg.exprs.len() > 1 {
                         let mut unit_deps = UnitDeps::new();
                         for expr in dbg.exprs.iter_mut() {
-                            unit_deps.extend(self.expr(expr, nested_tracked))
+                            unit_deps.extend(self.expr(expr))
                         }
                         mac.tokens = dbg.into_token_stream();
                         return (unit_deps, None);
@@ -378,16 +381,16 @@ impl Function {
             }
             "enclose" => {
                 if let Ok(mut enclose) = mac.parse_body::<EncloseBody>() {
-                    let deps = self.expr(&mut enclose.expr, nested_tracked);
+                    let deps = self.expr(&mut enclose.expr);
                     mac.tokens = enclose.into_token_stream();
                     return (deps, None);
                 }
             }
             "matches" => {
                 if let Ok(mut matches) = mac.parse_body::<MatchesBody>() {
-                    let mut unit_deps = self.expr(&mut matches.expr, nested_tracked);
+                    let mut unit_deps = self.expr(&mut matches.expr);
                     if let Some(if_expr) = &mut matches.if_expr {
-                        unit_deps.extend(self.expr(if_expr, nested_tracked));
+                        unit_deps.extend(self.expr(if_expr));
                     }
                     mac.tokens = matches.into_token_stream();
                     return (unit_deps, None);
@@ -395,7 +398,7 @@ impl Function {
             }
             "try" => {
                 if let Ok(mut try_) = mac.parse_body::<Try>() {
-                    let deps = self.expr(&mut try_.expr, nested_tracked);
+                    let deps = self.expr(&mut try_.expr);
                     mac.tokens = try_.into_token_stream();
                     return (deps, None);
                 }
@@ -404,14 +407,14 @@ impl Function {
                 if let Ok(mut vec) = mac.parse_body::<VecBody>() {
                     let unit_deps = match &mut vec {
                         VecBody::Repeat(repeat) => {
-                            let mut deps = self.expr(&mut repeat.expr, nested_tracked);
-                            deps.extend(self.expr(&mut repeat.n_expr, nested_tracked));
+                            let mut deps = self.expr(&mut repeat.expr);
+                            deps.extend(self.expr(&mut repeat.n_expr));
                             deps
                         }
                         VecBody::Literal(literal) => {
                             let mut unit_deps = UnitDeps::new();
                             for expr in literal.iter_mut() {
-                                unit_deps.extend(self.expr(expr, nested_tracked));
+                                unit_deps.extend(self.expr(expr));
                             }
                             unit_deps
                         }
@@ -425,9 +428,9 @@ impl Function {
                     let mut unit_deps = UnitDeps::new();
                     for expr in write.exprs.iter_mut().skip(1) {
                         if let Expr::Assign(assign) = expr {
-                            unit_deps.extend(self.expr(&mut assign.right, nested_tracked));
+                            unit_deps.extend(self.expr(&mut assign.right));
                         } else {
-                            unit_deps.extend(self.expr(expr, nested_tracked));
+                            unit_deps.extend(self.expr(expr));
                         }
                     }
                     mac.tokens = write.into_token_stream();
@@ -463,22 +466,34 @@ impl Function {
                                     }
                                 }
                             };
-                            unit_deps.extend(self.expr(&mut expr, is_keyed));
+                            if is_keyed {
+                                unit_deps.extend(self.keyed_access(&mut expr));
+                            } else {
+                                unit_deps.extend(self.expr(&mut expr));
+                            }
                             expr
                         }
                     };
                     let tracked_path = &mac.path;
                     let transformed = match &*name {
                         "tracked" | "tracked_keyed" => {
-                            if nested_tracked {
-                                quote_spanned! {mac_span=> #tracked_path!(#expr)}
-                            } else if is_expr_trivial {
+                            if is_expr_trivial {
                                 quote_spanned! {mac_span=>
                                     #tracked_path!({
                                         __avalanche_internal_gen = ::std::cmp::max((#expr).__avalanche_internal_gen, __avalanche_internal_gen);
                                         #expr
                                     })
                                 }
+                            } else if is_keyed && matches!(expr, Expr::Index(_) | Expr::Field(_)) {
+                                // Keyed values are usually read out of a container by index or
+                                // field, so borrow them rather than moving them out
+                                quote_spanned! {mac_span=>
+                                    #tracked_path!({
+                                        let value = &#expr;
+                                        __avalanche_internal_gen = ::std::cmp::max(__avalanche_internal_gen, value.__avalanche_internal_gen);
+                                        value
+                                    })
+                                }
                             } else {
                                 quote_spanned! {mac_span=>
                                     #tracked_path!({
@@ -489,17 +504,14 @@ impl Function {
                                 }
                             }
                         }
-                        "updated_keyed" => {
-                            quote_spanned! {mac_span=> #tracked_path!(@internal __avalanche_hook_context; (#expr).__avalanche_internal_gen)}
-                        }
-                        "updated" => quote_spanned! {mac_span=>
+                        "updated" | "updated_keyed" => quote_spanned! {mac_span=>
                             {
                                 let __avalanche_outer_gen = __avalanche_internal_gen;
                                 // Other tracked expressions in the parent expression but outside of the current instance of
                                 // updated!() should not influence the result
                                 __avalanche_internal_gen = #avalanche_path::tracked::Gen::escape_hatch_new(false);
                                 // #expr may modify __avalanche_internal_gen so this is not a noop
-                                __avalanche_internal_gen = ::std::cmp::max((#expr).__avalanche_internal_gen, __avalanche_internal_gen);
+                                __avalanche_internal_gen = ::std::cmp::max((&#expr).__avalanche_internal_gen, __avalanche_internal_gen);
                                 let __avalanche_updated = #tracked_path!(@internal __avalanche_hook_context; __avalanche_internal_gen);
                                 // Restore external gen, but update it if a higher one was found
                                 __avalanche_internal_gen = ::std::cmp::max(__avalanche_outer_gen, __avalanche_internal_gen);
@@ -521,6 +533,72 @@ impl Function {
         (UnitDeps::new(), None)
     }
 
+    /// Processes the body of a `tracked_keyed!` or `updated_keyed!` call, which is a
+    /// keyed value accessed through some container, as in `vec[i]` or `tracked!(data).rows[i]`.
+    /// The tracked containers on that access path are unwrapped without contributing their
+    /// generations, while index and argument expressions are tracked as usual.
+    fn keyed_access(&mut self, expr: &mut Expr) -> UnitDeps {
+        match expr {
+            Expr::Index(index) => {
+                let mut deps = self.keyed_container(&mut index.expr);
+                deps.extend(self.expr(&mut index.index));
+                deps
+            }
+            Expr::Field(field) => self.keyed_container(&mut field.base),
+            Expr::MethodCall(method) => {
+                let mut deps = self.keyed_container(&mut method.receiver);
+                for arg in method.args.iter_mut() {
+                    deps.extend(self.expr(arg));
+                }
+                deps
+            }
+            Expr::Paren(paren) => self.keyed_access(&mut paren.expr),
+            Expr::Group(group) => self.keyed_access(&mut group.expr),
+            Expr::Reference(reference) => self.keyed_access(&mut reference.expr),
+            Expr::Macro(macro_expr) if is_tracked_macro(&macro_expr.mac) => {
+                self.keyed_container(expr)
+            }
+            _ => self.expr(expr),
+        }
+    }
+
+    /// Processes a container on the access path of a keyed value. A bare identifier
+    /// or a `tracked!` call is unwrapped without registering its generation, but is still
+    /// recorded as a named dependency so closures reading keyed values stay correct.
+    fn keyed_container(&mut self, expr: &mut Expr) -> UnitDeps {
+        match expr {
+            Expr::Path(path) if path.path.get_ident().map_or(false, |ident| ident != "self") => {
+                let ident = path.path.get_ident().unwrap().clone();
+                let mut deps = UnitDeps::new();
+                deps.tracked_deps.insert(ident.clone());
+                *expr = parse_quote_spanned! {ident.span()=> (#ident).__avalanche_internal_value};
+                deps
+            }
+            Expr::Macro(macro_expr) if is_tracked_macro(&macro_expr.mac) => {
+                match macro_expr.mac.parse_body::<Tracked>() {
+                    Ok(Tracked::Named(ident)) => {
+                        let mut path: Expr = parse_quote! { #ident };
+                        let deps = self.keyed_container(&mut path);
+                        *expr = path;
+                        deps
+                    }
+                    Ok(Tracked::Unnamed(mut inner)) => {
+                        let deps = self.keyed_container(&mut inner);
+                        let span = inner.span();
+                        *expr = parse_quote_spanned! {span=> (#inner).__avalanche_internal_value};
+                        deps
+                    }
+                    Err(_) => self.expr(expr),
+                }
+            }
+            Expr::Index(_) | Expr::Field(_) | Expr::MethodCall(_) => self.keyed_access(expr),
+            Expr::Paren(paren) => self.keyed_container(&mut paren.expr),
+            Expr::Group(group) => self.keyed_container(&mut group.expr),
+            Expr::Reference(reference) => self.keyed_container(&mut reference.expr),
+            _ => self.expr(expr),
+        }
+    }
+
     /// Allow providing dependencies to closures being indirectly executed by functions.
     /// Returns trabsformed closure expr, which handles marking a closure as updated
     fn closure(&mut self, closure: &mut syn::ExprClosure, args_deps: UnitDeps) -> (UnitDeps, Expr) {
@@ -538,7 +616,7 @@ impl Function {
             Expr::Block(expr) => self.closure_block(&mut expr.block),
             Expr::Async(expr) => self.closure_block(&mut expr.block),
             _ => {
-                let mut deps = self.expr(&mut closure.body, false);
+                let mut deps = self.expr(&mut closure.body);
                 deps.tracked_deps
                     .retain(|dep| self.get_var_function_scope(&dep.to_string()).is_none());
                 deps
@@ -567,7 +645,6 @@ impl Function {
         &mut self,
         call: syn::ExprCall,
         path: &Path,
-        nested_tracked: bool,
     ) -> (UnitDeps, Expr) {
         let mut props = parse_component_call(call);
 
@@ -577,7 +654,7 @@ impl Function {
 
         // first prop must be the context `self`, which we skip
         for prop in props.iter_mut() {
-            let dependencies = self.expr(&mut prop.value, nested_tracked);
+            let dependencies = self.expr(&mut prop.value);
             let field_ident = &prop.name;
             let init_expr = &prop.value;
             let field_span = prop.value.span();
@@ -624,11 +701,11 @@ impl Function {
         (component_dependencies, transformed)
     }
 
-    fn expr(&mut self, expr: &mut Expr, nested_tracked: bool) -> UnitDeps {
-        self.escape_expr(expr, nested_tracked).dependencies
+    fn expr(&mut self, expr: &mut Expr) -> UnitDeps {
+        self.escape_expr(expr).dependencies
     }
 
-    fn escape_expr(&mut self, expr: &mut Expr, nested_tracked: bool) -> EscapeExprRet {
+    fn escape_expr(&mut self, expr: &mut Expr) -> EscapeExprRet {
         let mut dependencies: Option<UnitDeps> = None;
         let mut escape = false;
 
@@ -636,12 +713,12 @@ impl Function {
             Expr::Array(array) => {
                 let mut unit_deps = UnitDeps::new();
                 for expr in array.elems.iter_mut() {
-                    unit_deps.extend(self.expr(expr, nested_tracked));
+                    unit_deps.extend(self.expr(expr));
                 }
                 dependencies = Some(unit_deps);
             }
             Expr::Assign(assign) => {
-                let mut rhs = self.expr(&mut assign.right, nested_tracked);
+                let mut rhs = self.expr(&mut assign.right);
                 rhs.has_tracked = false;
                 enable_expr_tracking(&mut assign.right, &rhs);
                 let vars = from_expr(&assign.left, rhs.clone());
@@ -653,12 +730,12 @@ impl Function {
                 }
 
                 // TODO: should lhs be used as a return value
-                self.expr(&mut assign.left, nested_tracked);
+                self.expr(&mut assign.left);
 
                 dependencies = Some(rhs)
             }
             Expr::AssignOp(assign_op) => {
-                let mut rhs = self.expr(&mut assign_op.right, nested_tracked);
+                let mut rhs = self.expr(&mut assign_op.right);
                 rhs.has_tracked = false;
                 enable_expr_tracking(&mut assign_op.right, &rhs);
                 let lhs_vars = from_expr(&assign_op.left, rhs.clone());
@@ -669,17 +746,17 @@ impl Function {
                 }
 
                 // TODO: should lhs be used as a return value
-                self.expr(&mut assign_op.left, nested_tracked);
+                self.expr(&mut assign_op.left);
 
                 dependencies = Some(rhs)
             }
             Expr::Async(async_) => dependencies = Some(self.block(&mut async_.block)),
             Expr::Await(await_) => {
-                dependencies = Some(self.expr(&mut *await_.base, nested_tracked))
+                dependencies = Some(self.expr(&mut *await_.base))
             }
             Expr::Binary(binary) => {
-                let mut deps = self.expr(&mut binary.left, nested_tracked);
-                let rhs = self.expr(&mut binary.right, nested_tracked);
+                let mut deps = self.expr(&mut binary.left);
+                let rhs = self.expr(&mut binary.right);
                 deps.extend(rhs);
 
                 dependencies = Some(deps);
@@ -688,11 +765,11 @@ impl Function {
                 dependencies = Some(self.block(&mut block.block));
             }
             Expr::Box(expr_box) => {
-                dependencies = Some(self.expr(&mut expr_box.expr, nested_tracked))
+                dependencies = Some(self.expr(&mut expr_box.expr))
             }
             Expr::Break(break_expr) => match &mut break_expr.expr {
                 Some(expr) => {
-                    dependencies = Some(self.expr(expr, nested_tracked));
+                    dependencies = Some(self.expr(expr));
                     escape = true;
                 }
                 None => {}
@@ -701,14 +778,21 @@ impl Function {
                 let deps = match &*call.func {
                     Expr::Path(path) if is_component(call, &path.path) => {
                         let (deps, transformed) =
-                            self.component(call.clone(), &path.path, nested_tracked);
+                            self.component(call.clone(), &path.path);
                         *expr = transformed;
                         deps
                     }
                     _ => {
-                        let mut deps = self.expr(&mut call.func, nested_tracked);
+                        if self.repeated_depth > 0 && is_unkeyed_hook(call) {
+                            emit_error!(
+                                call,
+                                "hooks called within loops or iterator closures share their state across iterations";
+                                help = "pass `self.with_key(key)` instead of `self`, or use `state_keyed` or `store_keyed`, with a key unique to each iteration"
+                            );
+                        }
+                        let mut deps = self.expr(&mut call.func);
                         for arg in call.args.iter_mut() {
-                            let arg_dep = self.expr(arg, nested_tracked);
+                            let arg_dep = self.expr(arg);
                             deps.extend(arg_dep);
                         }
                         deps
@@ -718,7 +802,7 @@ impl Function {
                 dependencies = Some(deps);
             }
             Expr::Cast(cast) => {
-                dependencies = Some(self.expr(&mut cast.expr, nested_tracked));
+                dependencies = Some(self.expr(&mut cast.expr));
             }
             Expr::Closure(closure) => {
                 // TODO: actual data for second arg? Otherwise, remove it from closure
@@ -728,7 +812,7 @@ impl Function {
             }
             Expr::Continue(_) => {}
             Expr::Field(field) => {
-                let deps = self.expr(&mut field.base, nested_tracked);
+                let deps = self.expr(&mut field.base);
                 dependencies = Some(deps);
             }
             Expr::ForLoop(for_expr) => {
@@ -736,14 +820,16 @@ impl Function {
                 // for pat in expr {}
                 let mut scope = Scope::new();
 
-                let expr = self.expr(&mut for_expr.expr, nested_tracked);
+                let expr = self.expr(&mut for_expr.expr);
 
                 let vars = vars_from_pat(&for_expr.pat, expr);
                 scope.vars = vars;
                 self.scopes.push(scope);
 
                 // Get dependencies of the for expr
+                self.repeated_depth += 1;
                 dependencies = Some(self.block(&mut for_expr.body));
+                self.repeated_depth -= 1;
 
                 //variables created by pat no longer present
                 self.scopes.pop();
@@ -751,7 +837,7 @@ impl Function {
                 // TODO: effect deps
             }
             Expr::Group(group) => {
-                dependencies = Some(self.expr(&mut group.expr, nested_tracked));
+                dependencies = Some(self.expr(&mut group.expr));
             }
             Expr::If(if_expr) => {
                 // TODO: handle conditional dependency updates within block
@@ -763,10 +849,10 @@ impl Function {
 
                 //this scope is for variables created via let.
                 let mut if_scope = Scope::new();
-                let cond_dependencies = self.expr(&mut if_expr.cond, nested_tracked);
+                let cond_dependencies = self.expr(&mut if_expr.cond);
 
                 if let Expr::Let(let_expr) = &mut *if_expr.cond {
-                    let let_dependencies = self.expr(&mut let_expr.expr, nested_tracked);
+                    let let_dependencies = self.expr(&mut let_expr.expr);
                     if_scope.vars = vars_from_pat(&let_expr.pat, let_dependencies);
                 }
 
@@ -776,7 +862,7 @@ impl Function {
                 let mut deps = self.block(&mut if_expr.then_branch);
                 deps.extend(cond_dependencies);
                 match &mut if_expr.else_branch {
-                    Some(else_branch) => deps.extend(self.expr(&mut else_branch.1, nested_tracked)),
+                    Some(else_branch) => deps.extend(self.expr(&mut else_branch.1)),
                     None => {}
                 }
 
@@ -786,8 +872,8 @@ impl Function {
                 dependencies = Some(deps);
             }
             Expr::Index(index) => {
-                let mut deps = self.expr(&mut index.expr, nested_tracked);
-                let index = self.expr(&mut index.index, nested_tracked);
+                let mut deps = self.expr(&mut index.expr);
+                let index = self.expr(&mut index.index);
                 deps.extend(index);
                 dependencies = Some(deps);
             }
@@ -796,38 +882,49 @@ impl Function {
                 // but instead is special-cased by If
                 // as variables may be created within its block
                 // however, this is implemented here for completion's sake
-                dependencies = Some(self.expr(&mut let_expr.expr, nested_tracked));
+                dependencies = Some(self.expr(&mut let_expr.expr));
             }
             Expr::Lit(_) => {}
             Expr::Loop(loop_expr) => {
+                self.repeated_depth += 1;
                 dependencies = Some(self.block(&mut loop_expr.body));
+                self.repeated_depth -= 1;
             }
             Expr::Macro(macro_expr) => {
-                let (deps, transformed) = self.mac(&mut macro_expr.mac, nested_tracked);
+                let (deps, transformed) = self.mac(&mut macro_expr.mac);
                 if let Some(transformed) = transformed {
                     *expr = transformed;
                 }
                 dependencies = Some(deps);
             }
             Expr::Match(match_expr) => {
-                let mut deps = self.expr(&mut match_expr.expr, nested_tracked);
+                let mut deps = self.expr(&mut match_expr.expr);
                 for arm in match_expr.arms.iter_mut() {
-                    deps.extend(self.expr(&mut arm.body, nested_tracked));
+                    deps.extend(self.expr(&mut arm.body));
                     if let Some(if_guard) = &mut arm.guard {
-                        deps.extend(self.expr(&mut *if_guard.1, nested_tracked));
+                        deps.extend(self.expr(&mut *if_guard.1));
                     }
                 }
                 dependencies = Some(deps);
             }
             Expr::MethodCall(method) => {
-                let mut deps = self.expr(&mut method.receiver, nested_tracked);
+                // Closures passed to iterator adapters may be called once per item
+                let is_adapter = is_iterator_adapter(method);
+                let mut deps = self.expr(&mut method.receiver);
                 for arg in method.args.iter_mut() {
-                    deps.extend(self.expr(arg, nested_tracked));
+                    let is_closure = is_adapter && matches!(arg, Expr::Closure(_));
+                    if is_closure {
+                        self.repeated_depth += 1;
+                    }
+                    deps.extend(self.expr(arg));
+                    if is_closure {
+                        self.repeated_depth -= 1;
+                    }
                 }
                 dependencies = Some(deps)
             }
             Expr::Paren(paren) => {
-                dependencies = Some(self.expr(&mut paren.expr, nested_tracked));
+                dependencies = Some(self.expr(&mut paren.expr));
             }
             Expr::Path(path) => {
                 // No dependencies; dependencies are only created by tracked!()
@@ -841,28 +938,28 @@ impl Function {
                 // range.from.as_ref().map(|r| dependencies.extend(self.expr(&r).drain()));
                 // range.to.as_ref().map(|r| dependencies.extend(self.expr(&r).drain()));
                 //let range = range.clone();
-                let mut deps = range.from.as_mut().map(|r| self.expr(r, nested_tracked));
+                let mut deps = range.from.as_mut().map(|r| self.expr(r));
                 if let Some(to) = &mut range.to {
                     match &mut deps {
                         Some(deps) => {
-                            deps.extend(self.expr(to, nested_tracked));
+                            deps.extend(self.expr(to));
                         }
                         None => {
-                            deps = Some(self.expr(to, nested_tracked));
+                            deps = Some(self.expr(to));
                         }
                     }
                 }
                 dependencies = deps
             }
             Expr::Reference(reference) => {
-                dependencies = Some(self.expr(&mut reference.expr, nested_tracked));
+                dependencies = Some(self.expr(&mut reference.expr));
             }
             Expr::Repeat(repeat) => {
-                dependencies = Some(self.expr(&mut repeat.expr, nested_tracked));
+                dependencies = Some(self.expr(&mut repeat.expr));
             }
             Expr::Return(ret) => match &mut ret.expr {
                 Some(expr) => {
-                    dependencies = Some(self.expr(expr, nested_tracked));
+                    dependencies = Some(self.expr(expr));
                     escape = true;
                 }
                 None => {}
@@ -872,21 +969,21 @@ impl Function {
                 let mut deps = struct_expr
                     .rest
                     .as_mut()
-                    .map(|rest| self.expr(rest, nested_tracked));
+                    .map(|rest| self.expr(rest));
                 for field in struct_expr.fields.iter_mut() {
                     match &mut deps {
                         Some(deps) => {
-                            deps.extend(self.expr(&mut field.expr, nested_tracked));
+                            deps.extend(self.expr(&mut field.expr));
                         }
                         None => {
-                            deps = Some(self.expr(&mut field.expr, nested_tracked));
+                            deps = Some(self.expr(&mut field.expr));
                         }
                     }
                 }
                 dependencies = deps;
             }
             Expr::Try(try_expr) => {
-                dependencies = Some(self.expr(&mut try_expr.expr, nested_tracked));
+                dependencies = Some(self.expr(&mut try_expr.expr));
             }
             Expr::TryBlock(try_block) => {
                 dependencies = Some(self.block(&mut try_block.block));
@@ -894,14 +991,14 @@ impl Function {
             Expr::Tuple(tuple) => {
                 let mut deps = UnitDeps::new();
                 for expr in tuple.elems.iter_mut() {
-                    deps.extend(self.expr(expr, nested_tracked));
+                    deps.extend(self.expr(expr));
                 }
 
                 dependencies = Some(deps);
             }
             Expr::Type(_) => {}
             Expr::Unary(unary) => {
-                dependencies = Some(self.expr(&mut unary.expr, nested_tracked));
+                dependencies = Some(self.expr(&mut unary.expr));
             }
             Expr::Unsafe(unsafe_block) => {
                 dependencies = Some(self.block(&mut unsafe_block.block));
@@ -910,7 +1007,9 @@ impl Function {
             Expr::While(while_expr) => {
                 //while loops have value ()
                 //() has no dependencies, so none are returned here
+                self.repeated_depth += 1;
                 dependencies = Some(self.block(&mut while_expr.body));
+                self.repeated_depth -= 1;
             }
             Expr::Yield(_) => {
                 abort!(expr, "yield unsupported")
@@ -1184,6 +1283,67 @@ fn from_expr_numbered(lhs: &[&Expr], rhs: UnitDeps) -> Vec<Var> {
     vars
 }
 
+/// The names of the core hooks whose state is identified only by the location of their call.
+/// Hooks from other crates are not checked, as a name alone cannot identify them.
+const UNKEYED_HOOKS: &[&str] = &["state", "store"];
+
+/// The iterator adapters whose closure arguments may be called once per item.
+const ITERATOR_ADAPTERS: &[&str] = &[
+    "all", "any", "filter", "filter_map", "find", "find_map", "flat_map", "fold", "for_each",
+    "inspect", "map", "map_while", "max_by", "max_by_key", "min_by", "min_by_key", "position",
+    "reduce", "scan", "skip_while", "take_while", "try_fold", "try_for_each",
+];
+
+/// The methods that create an iterator from their receiver.
+const ITERATOR_SOURCES: &[&str] = &[
+    "bytes", "chain", "char_indices", "chars", "cloned", "copied", "cycle", "drain", "enumerate",
+    "into_iter", "into_keys", "into_values", "iter", "iter_mut", "keys", "lines", "peekable", "rev",
+    "skip", "split", "split_whitespace", "step_by", "take", "values", "values_mut", "windows",
+    "chunks", "zip",
+];
+
+/// Whether the given method call is an iterator adapter taking a closure that may be called
+/// once per item. Only receivers that visibly produce an iterator are recognized, so adapters
+/// called on an iterator stored in a variable are not detected, while methods like
+/// `Option::map` are not mistaken for adapters.
+fn is_iterator_adapter(method: &syn::ExprMethodCall) -> bool {
+    ITERATOR_ADAPTERS
+        .iter()
+        .any(|adapter| method.method == adapter)
+        && is_iterator(&method.receiver)
+}
+
+/// Whether the given expression visibly produces an iterator.
+fn is_iterator(expr: &Expr) -> bool {
+    match expr {
+        Expr::Range(_) => true,
+        Expr::Paren(paren) => is_iterator(&paren.expr),
+        Expr::MethodCall(method) => {
+            ITERATOR_SOURCES
+                .iter()
+                .any(|source| method.method == source)
+                || is_iterator_adapter(method)
+        }
+        _ => false,
+    }
+}
+
+/// Whether the given call is to one of the core unkeyed hooks, whose state is
+/// identified only by the location of the call.
+fn is_unkeyed_hook(call: &syn::ExprCall) -> bool {
+    let is_hook = match &*call.func {
+        Expr::Path(path) => path.path.segments.last().map_or(false, |last| {
+            UNKEYED_HOOKS.iter().any(|hook| last.ident == hook)
+        }),
+        _ => false,
+    };
+    let valid_self = call.args.first().map_or(false, |param| match param {
+        Expr::Path(path) => path.path.get_ident().map_or(false, |ident| ident == "self"),
+        _ => false,
+    });
+    is_hook && valid_self
+}
+
 /// Whether the given path and first parameter indicate a function call is a component call.
 /// True if the (qualified) identifier is not raw and begins with a capital ASCII character,
 fn is_component(call: &syn::ExprCall, path: &Path) -> bool {
@@ -1239,3 +1399,11 @@ fn parse_component_call(call: syn::ExprCall) -> Vec<ComponentProp> {
     }
     props
 }
+
+/// Whether the given macro is a `tracked!` or `tracked_keyed!` call.
+fn is_tracked_macro(mac: &syn::Macro) -> bool {
+    mac.path
+        .segments
+        .last()
+        .map_or(false, |last| last.ident == "tracked" || last.ident == "tracked_keyed")
+}
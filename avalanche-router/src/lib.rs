@@ -0,0 +1,56 @@
+//! A client-side router for `avalanche-web`.
+//!
+//! The current route is read with the [location] hook, and changed with a [Navigator], a [Link],
+//! or a [Redirect], none of which reload the page. Routes are stored in the URL's path by default,
+//! or in its fragment after calling [set_mode] with [Mode::Hash].
+//!
+//! Clicks on a [Link] navigate without reloading the page. While any component calling [location]
+//! is mounted, so do clicks on plain `A` elements whose `href` is a route within the app: in
+//! [Mode::History], a URL of the same origin, other than a fragment of the current page, and in
+//! [Mode::Hash], a fragment of the current page. Clicks are left to the browser if a modifier key
+//! is held, a mouse button other than the primary one is used, the link has a `target` or
+//! `download` attribute, or a click handler calls `prevent_default`. As in [Mode::History] plain
+//! links are resolved against the current URL, their `href` should be an absolute path, like `/about`.
+//!
+//! Routes are matched against patterns with [Location::matches]. Nested routes match the
+//! [rest](Params::rest) of the path captured by a trailing `*`:
+//!
+//! ```rust
+//! use avalanche::{component, tracked, View};
+//! use avalanche_router::{location, match_path, Link};
+//! use avalanche_web::components::{Div, Text};
+//!
+//! #[component]
+//! fn App() -> View {
+//!     let (location, _) = location(self);
+//!     if tracked!(location).matches("/").is_some() {
+//!         Link(self, to = "/users/42", Text(self, "Profile"))
+//!     } else if let Some(params) = tracked!(location).matches("/users/:id/*") {
+//!         User(
+//!             self,
+//!             id = params.get("id").unwrap_or_default(),
+//!             rest = params.rest().to_owned()
+//!         )
+//!     } else {
+//!         Text(self, "Not found")
+//!     }
+//! }
+//!
+//! #[component]
+//! fn User(id: u32, rest: String) -> View {
+//!     let page = if match_path("/posts", &tracked!(rest)).is_some() {
+//!         "posts"
+//!     } else {
+//!         "profile"
+//!     };
+//!     Div(self, Text(self, format!("User {}'s {}", tracked!(id), tracked!(page))))
+//! }
+//! ```
+
+mod link;
+mod location;
+mod path;
+
+pub use link::{Link, Redirect};
+pub use location::{href, location, mode, set_mode, Location, Mode, Navigator};
+pub use path::{match_path, Params};
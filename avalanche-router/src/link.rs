@@ -0,0 +1,373 @@
+use std::borrow::Cow;
+use std::cmp::max;
+
+use avalanche::alloc::Bump;
+use avalanche::tracked::Gen;
+use avalanche::{component, tracked, View};
+use avalanche_web::class_list::ClassList;
+use avalanche_web::components::{Dir, RawElement, Translate, A};
+use avalanche_web::events::{ListenerOptions, TypedEvent};
+use avalanche_web::style::Style;
+use avalanche_web::NodeRef;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    AnimationEvent, CompositionEvent, DragEvent, Event, FocusEvent, HtmlAnchorElement,
+    KeyboardEvent, MouseEvent, PointerEvent, ProgressEvent, TouchEvent, TransitionEvent,
+    WheelEvent,
+};
+
+use crate::location::{href, is_plain_click, Location, Navigator};
+
+type ClickHandler<'a> = Box<dyn Fn(TypedEvent<MouseEvent, HtmlAnchorElement>) + 'a>;
+
+/// A link to a route, rendered as an `a` element.
+///
+/// Clicking a `Link` navigates to the route `to` without reloading the page, unless a modifier key
+/// is held, a mouse button other than the primary one is used, or the link has a `target` or
+/// `download` attribute, so that opening the link in a new tab keeps working. If `replace` is true,
+/// the current entry in the session history is replaced instead of a new one being added.
+///
+/// Every prop of [A] other than `href` may be given too. Click handlers run before the navigation,
+/// which they may cancel by calling `prevent_default`.
+///
+/// # Example
+/// ```rust
+/// use avalanche::{component, View};
+/// use avalanche_router::Link;
+/// use avalanche_web::components::{Nav, Text};
+///
+/// #[component]
+/// fn Menu() -> View {
+///     Nav(self, [
+///         Link(self, to = "/", Text(self, "Home")),
+///         Link(self, to = "/users/42", class = "user", aria_current = "page", Text(self, "Profile")),
+///     ])
+/// }
+/// ```
+pub struct Link<'a> {
+    a: A<'a>,
+    to: Option<Cow<'a, str>>,
+    replace: bool,
+    /// The bubble phase click handler, run by the listener navigating to `to`.
+    on_click: Option<(ListenerOptions, ClickHandler<'a>)>,
+    /// The newest generation of `to`, `replace`, and `on_click`.
+    gen: Gen<'a>,
+}
+
+impl<'a> Link<'a> {
+    pub fn new<'bump: 'a>(bump: &'bump Bump) -> Self {
+        Self {
+            a: A::new(bump),
+            to: None,
+            replace: false,
+            on_click: None,
+            gen: Gen::escape_hatch_new(false),
+        }
+    }
+
+    pub fn build(self, location: (u32, u32)) -> RawElement<'a> {
+        let to = self.to.expect("Link requires a `to` route");
+        let replace = self.replace;
+        let (options, on_click) = match self.on_click {
+            Some((options, on_click)) => (options, Some(on_click)),
+            None => (ListenerOptions::default(), None),
+        };
+        // the listener must be able to prevent the browser from following the link
+        let options = ListenerOptions {
+            passive: false,
+            ..options
+        };
+        self.a
+            .href(href(&to), self.gen)
+            .on_click_with(
+                (options, move |e: TypedEvent<MouseEvent, HtmlAnchorElement>| {
+                    if let Some(on_click) = &on_click {
+                        on_click(e.clone());
+                    }
+                    match e.current_target() {
+                        Some(anchor) if is_plain_click(&e, &anchor) => {}
+                        _ => return,
+                    }
+                    e.prevent_default();
+                    if replace {
+                        Navigator::new().replace(&to);
+                    } else {
+                        Navigator::new().push(&to);
+                    }
+                }),
+                self.gen,
+            )
+            .build(location)
+    }
+
+    /// The route to navigate to, like `/users/42?tab=posts`.
+    pub fn to(mut self, val: impl Into<Cow<'a, str>>, gen: Gen<'a>) -> Self {
+        self.to = Some(val.into());
+        self.gen = max(self.gen, gen);
+        self
+    }
+
+    /// Whether to replace the current entry in the session history instead of adding one.
+    pub fn replace(mut self, val: bool, gen: Gen<'a>) -> Self {
+        self.replace = val;
+        self.gen = max(self.gen, gen);
+        self
+    }
+
+    pub fn children<I: IntoIterator<Item = View>>(mut self, children: I, gen: Gen<'a>) -> Self {
+        self.a = self.a.children(children, gen);
+        self
+    }
+
+    pub fn __last<I: IntoIterator<Item = View>>(self, children: I, gen: Gen<'a>) -> Self {
+        self.children(children, gen)
+    }
+
+    pub fn classes(mut self, classes: impl Into<ClassList<'a>>, gen: Gen<'a>) -> Self {
+        self.a = self.a.classes(classes, gen);
+        self
+    }
+
+    pub fn styles(mut self, styles: Style<'a>, gen: Gen<'a>) -> Self {
+        self.a = self.a.styles(styles, gen);
+        self
+    }
+
+    pub fn node_ref(mut self, node_ref: NodeRef<HtmlAnchorElement>, gen: Gen<'a>) -> Self {
+        self.a = self.a.node_ref(node_ref, gen);
+        self
+    }
+
+    pub fn hidden(mut self, val: bool, gen: Gen<'a>) -> Self {
+        self.a = self.a.hidden(val, gen);
+        self
+    }
+
+    pub fn on_click(
+        self,
+        f: impl Fn(TypedEvent<MouseEvent, HtmlAnchorElement>) + 'a,
+        gen: Gen<'a>,
+    ) -> Self {
+        self.on_click_with((ListenerOptions::default(), f), gen)
+    }
+
+    pub fn on_click_with(
+        mut self,
+        (options, f): (
+            ListenerOptions,
+            impl Fn(TypedEvent<MouseEvent, HtmlAnchorElement>) + 'a,
+        ),
+        gen: Gen<'a>,
+    ) -> Self {
+        // capture listeners are attached separately from the navigating listener
+        if options.capture {
+            self.a = self.a.on_click_with((options, f), gen);
+        } else {
+            self.on_click = Some((options, Box::new(f)));
+            self.gen = max(self.gen, gen);
+        }
+        self
+    }
+}
+
+/// Defines methods passing props through to the `A` element.
+macro_rules! forward_props {
+    ($($ident:ident : $ty:ty),* $(,)?) => {
+        impl<'a> Link<'a> {
+            $(
+                pub fn $ident(mut self, val: impl Into<$ty>, gen: Gen<'a>) -> Self {
+                    self.a = self.a.$ident(val.into(), gen);
+                    self
+                }
+            )*
+        }
+    };
+}
+
+/// Defines methods passing listener props through to the `A` element.
+macro_rules! forward_listeners {
+    ($($ident:ident, $with_ident:ident : $ty:ty),* $(,)?) => {
+        impl<'a> Link<'a> {
+            $(
+                pub fn $ident(
+                    mut self,
+                    f: impl Fn(TypedEvent<$ty, HtmlAnchorElement>) + 'a,
+                    gen: Gen<'a>,
+                ) -> Self {
+                    self.a = self.a.$ident(f, gen);
+                    self
+                }
+
+                pub fn $with_ident(
+                    mut self,
+                    options_and_f: (ListenerOptions, impl Fn(TypedEvent<$ty, HtmlAnchorElement>) + 'a),
+                    gen: Gen<'a>,
+                ) -> Self {
+                    self.a = self.a.$with_ident(options_and_f, gen);
+                    self
+                }
+            )*
+        }
+    };
+}
+
+forward_props! {
+    access_key: Cow<'a, str>,
+    class: Cow<'a, str>,
+    content_editable: bool,
+    dir: Dir,
+    draggable: bool,
+    id: Cow<'a, str>,
+    lang: Cow<'a, str>,
+    placeholder: Cow<'a, str>,
+    slot: Cow<'a, str>,
+    spell_check: bool,
+    style: Cow<'a, str>,
+    tab_index: i16,
+    title: Cow<'a, str>,
+    translate: Translate,
+    role: Cow<'a, str>,
+    aria_controls: Cow<'a, str>,
+    aria_current: Cow<'a, str>,
+    aria_described_by: Cow<'a, str>,
+    aria_disabled: Cow<'a, str>,
+    aria_expanded: Cow<'a, str>,
+    aria_has_popup: Cow<'a, str>,
+    aria_hidden: Cow<'a, str>,
+    aria_label: Cow<'a, str>,
+    aria_labelled_by: Cow<'a, str>,
+    aria_live: Cow<'a, str>,
+    aria_pressed: Cow<'a, str>,
+    aria_selected: Cow<'a, str>,
+    download: Cow<'a, str>,
+    href_lang: Cow<'a, str>,
+    ping: Cow<'a, str>,
+    referrer_policy: Cow<'a, str>,
+    rel: Cow<'a, str>,
+    target: Cow<'a, str>,
+    type_: Cow<'a, str>,
+}
+
+forward_listeners! {
+    on_blur, on_blur_with: FocusEvent,
+    on_focus, on_focus_with: FocusEvent,
+    on_composition_start, on_composition_start_with: CompositionEvent,
+    on_composition_update, on_composition_update_with: CompositionEvent,
+    on_composition_end, on_composition_end_with: CompositionEvent,
+    on_change, on_change_with: Event,
+    on_input, on_input_with: Event,
+    on_reset, on_reset_with: Event,
+    on_submit, on_submit_with: Event,
+    on_invalid, on_invalid_with: Event,
+    on_load, on_load_with: Event,
+    on_error, on_error_with: Event,
+    on_key_down, on_key_down_with: KeyboardEvent,
+    on_key_up, on_key_up_with: KeyboardEvent,
+    on_can_play, on_can_play_with: Event,
+    on_can_play_through, on_can_play_through_with: Event,
+    on_duration_change, on_duration_change_with: Event,
+    on_emptied, on_emptied_with: Event,
+    on_ended, on_ended_with: Event,
+    on_loaded_data, on_loaded_data_with: Event,
+    on_loaded_metadata, on_loaded_metadata_with: Event,
+    on_pause, on_pause_with: Event,
+    on_play, on_play_with: Event,
+    on_playing, on_playing_with: Event,
+    on_rate_change, on_rate_change_with: Event,
+    on_seeked, on_seeked_with: Event,
+    on_seeking, on_seeking_with: Event,
+    on_stalled, on_stalled_with: Event,
+    on_suspend, on_suspend_with: Event,
+    on_time_update, on_time_update_with: Event,
+    on_volume_change, on_volume_change_with: Event,
+    on_waiting, on_waiting_with: Event,
+    on_aux_click, on_aux_click_with: MouseEvent,
+    on_context_menu, on_context_menu_with: MouseEvent,
+    on_double_click, on_double_click_with: MouseEvent,
+    on_mouse_down, on_mouse_down_with: MouseEvent,
+    on_mouse_enter, on_mouse_enter_with: MouseEvent,
+    on_mouse_leave, on_mouse_leave_with: MouseEvent,
+    on_mouse_move, on_mouse_move_with: MouseEvent,
+    on_mouse_over, on_mouse_over_with: MouseEvent,
+    on_mouse_out, on_mouse_out_with: MouseEvent,
+    on_mouse_up, on_mouse_up_with: MouseEvent,
+    on_pointer_lock_change, on_pointer_lock_change_with: Event,
+    on_pointer_lock_error, on_pointer_lock_error_with: Event,
+    on_select, on_select_with: Event,
+    on_wheel, on_wheel_with: WheelEvent,
+    on_drag, on_drag_with: DragEvent,
+    on_drag_end, on_drag_end_with: DragEvent,
+    on_drag_enter, on_drag_enter_with: DragEvent,
+    on_drag_start, on_drag_start_with: DragEvent,
+    on_drag_leave, on_drag_leave_with: DragEvent,
+    on_drag_over, on_drag_over_with: DragEvent,
+    on_drop, on_drop_with: DragEvent,
+    on_touch_cancel, on_touch_cancel_with: TouchEvent,
+    on_touch_end, on_touch_end_with: TouchEvent,
+    on_touch_move, on_touch_move_with: TouchEvent,
+    on_touch_start, on_touch_start_with: TouchEvent,
+    on_pointer_over, on_pointer_over_with: PointerEvent,
+    on_pointer_enter, on_pointer_enter_with: PointerEvent,
+    on_pointer_down, on_pointer_down_with: PointerEvent,
+    on_pointer_move, on_pointer_move_with: PointerEvent,
+    on_pointer_up, on_pointer_up_with: PointerEvent,
+    on_pointer_cancel, on_pointer_cancel_with: PointerEvent,
+    on_pointer_out, on_pointer_out_with: PointerEvent,
+    on_pointer_leave, on_pointer_leave_with: PointerEvent,
+    on_got_pointer_capture, on_got_pointer_capture_with: PointerEvent,
+    on_lost_pointer_capture, on_lost_pointer_capture_with: PointerEvent,
+    on_scroll, on_scroll_with: Event,
+    on_animation_start, on_animation_start_with: AnimationEvent,
+    on_animation_cancel, on_animation_cancel_with: AnimationEvent,
+    on_animation_end, on_animation_end_with: AnimationEvent,
+    on_animation_interaction, on_animation_interaction_with: AnimationEvent,
+    on_transition_start, on_transition_start_with: TransitionEvent,
+    on_transition_cancel, on_transition_cancel_with: TransitionEvent,
+    on_transition_end, on_transition_end_with: TransitionEvent,
+    on_transition_run, on_transition_run_with: TransitionEvent,
+    on_abort, on_abort_with: Event,
+    on_load_start, on_load_start_with: ProgressEvent,
+    on_progress, on_progress_with: ProgressEvent,
+}
+
+/// Navigates to the route `to` once rendered, replacing the current entry in the session history.
+/// The navigation is scheduled to run after the render, rather than during it.
+///
+/// Nothing is rendered. This is useful for routes that have moved, or that require a condition,
+/// like being logged in, to be met:
+///
+/// ```rust
+/// use avalanche::{component, tracked, View};
+/// use avalanche_router::Redirect;
+///
+/// #[component]
+/// fn Account(logged_in: bool) -> View {
+///     if tracked!(logged_in) {
+///         ().into()
+///     } else {
+///         Redirect(self, to = "/login")
+///     }
+/// }
+/// ```
+#[component]
+pub fn Redirect(to: &str) -> View {
+    if Location::current() != Location::parse(tracked!(to)) {
+        replace_after_render(tracked!(to).to_owned());
+    }
+    ().into()
+}
+
+/// Navigates to `route` in a new task, as navigating rerenders the components reading the location,
+/// which must wait until the render in progress completes.
+fn replace_after_render(route: String) {
+    let navigate = Closure::once_into_js(move || {
+        if Location::current() != Location::parse(&route) {
+            Navigator::new().replace(&route);
+        }
+    });
+    web_sys::window()
+        .unwrap()
+        .set_timeout_with_callback(navigate.unchecked_ref())
+        .unwrap();
+}
@@ -0,0 +1,402 @@
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+use std::str::FromStr;
+
+use avalanche::hooks::HookContext;
+use avalanche::{state, tracked, Tracked};
+use gloo_events::EventListener;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Element, HtmlAnchorElement, MouseEvent};
+
+use crate::path::{decode, match_path, Params};
+
+/// The event dispatched on the window after navigating with a [Navigator], as the History API
+/// only dispatches `popstate` for navigations by the user.
+const NAVIGATE_EVENT: &str = "avalanche-router-navigate";
+
+thread_local! {
+    static MODE: Cell<Mode> = Cell::new(Mode::History);
+    /// The document's click listener navigating within the app, while a `location` hook holds it.
+    static LINK_INTERCEPTOR: RefCell<Weak<EventListener>> = RefCell::new(Weak::new());
+}
+
+/// Where in the URL the current route is stored.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// The route is the URL's path and query string, like `/users/42?tab=posts`, changed through the History API.
+    /// The server must respond to every route's URL with the app.
+    History,
+    /// The route is stored in the URL's fragment, like `/#/users/42?tab=posts`. This works
+    /// when the server only serves the app from a single URL.
+    Hash,
+}
+
+/// Sets where in the URL the current route is stored. Defaults to [Mode::History].
+///
+/// This should be called before mounting any components using the router.
+pub fn set_mode(mode: Mode) {
+    MODE.with(|current| current.set(mode));
+}
+
+/// Returns where in the URL the current route is stored.
+pub fn mode() -> Mode {
+    MODE.with(Cell::get)
+}
+
+/// A route, made of a path and a query string.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Location {
+    path: String,
+    query: String,
+}
+
+impl Location {
+    /// Parses a route like `/users/42?tab=posts`. A fragment, if any, is ignored.
+    pub fn parse(route: &str) -> Self {
+        let route = route.split('#').next().unwrap_or_default();
+        let (path, query) = route.split_once('?').unwrap_or((route, ""));
+        Self {
+            path: if path.is_empty() { "/".to_owned() } else { path.to_owned() },
+            query: query.to_owned(),
+        }
+    }
+
+    /// Returns the current route, read from the URL according to the current [mode].
+    pub fn current() -> Self {
+        let location = web_sys::window().unwrap().location();
+        match mode() {
+            Mode::History => {
+                let path = location.pathname().unwrap_or_default();
+                let search = location.search().unwrap_or_default();
+                Self::parse(&format!("{}{}", path, search))
+            }
+            Mode::Hash => {
+                let hash = location.hash().unwrap_or_default();
+                Self::parse(hash.strip_prefix('#').unwrap_or(&hash))
+            }
+        }
+    }
+
+    /// Returns the path of the route, like `/users/42`.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the query string of the route without the leading `?`, like `tab=posts`.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Returns an iterator over the decoded names and values of the query string's parameters.
+    pub fn query_params(&self) -> impl Iterator<Item = (String, String)> + '_ {
+        self.query
+            .split('&')
+            .filter(|param| !param.is_empty())
+            .map(|param| {
+                let (name, value) = param.split_once('=').unwrap_or((param, ""));
+                (decode(name, true), decode(value, true))
+            })
+    }
+
+    /// Returns the value of the first query parameter named `name`, parsed as `T`, or `None` if
+    /// there is no such parameter or its value fails to parse.
+    pub fn query_param<T: FromStr>(&self, name: &str) -> Option<T> {
+        self.query_params()
+            .find(|(param, _)| param == name)
+            .and_then(|(_, value)| value.parse().ok())
+    }
+
+    /// Matches the route's path against `pattern`, as described in [match_path].
+    pub fn matches(&self, pattern: &str) -> Option<Params> {
+        match_path(pattern, &self.path)
+    }
+}
+
+/// Navigates between routes, changing the URL without reloading the page.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Navigator {
+    _private: (),
+}
+
+impl Navigator {
+    /// Creates a new navigator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Navigates to `route`, like `/users/42?tab=posts`, adding an entry to the session history.
+    /// Navigating to the current route replaces its entry instead.
+    pub fn push(&self, route: &str) {
+        let replace = Location::parse(route) == Location::current();
+        navigate(route, replace);
+    }
+
+    /// Navigates to `route`, replacing the current entry in the session history.
+    pub fn replace(&self, route: &str) {
+        navigate(route, true);
+    }
+
+    /// Navigates to the previous entry in the session history, like the browser's back button.
+    pub fn back(&self) {
+        let _ = web_sys::window().unwrap().history().and_then(|history| history.back());
+    }
+
+    /// Navigates to the next entry in the session history, like the browser's forward button.
+    pub fn forward(&self) {
+        let _ = web_sys::window().unwrap().history().and_then(|history| history.forward());
+    }
+}
+
+/// Returns the URL of `route` according to the current [mode], for use as a link's `href`.
+pub fn href(route: &str) -> String {
+    match mode() {
+        Mode::History => route.to_owned(),
+        Mode::Hash => format!("#{}", route),
+    }
+}
+
+fn navigate(route: &str, replace: bool) {
+    let window = web_sys::window().unwrap();
+    let history = window.history().expect("history");
+    let url = href(route);
+    let result = if replace {
+        history.replace_state_with_url(&JsValue::NULL, "", Some(&url))
+    } else {
+        history.push_state_with_url(&JsValue::NULL, "", Some(&url))
+    };
+    result.expect("navigation within the same origin");
+
+    let event = web_sys::Event::new(NAVIGATE_EVENT).unwrap();
+    window.dispatch_event(&event).unwrap();
+}
+
+/// Whether the click `event` on `anchor` should navigate within the app instead of being left to
+/// the browser: it is an unhandled click of the primary button without modifier keys, on a link
+/// opening in the same browsing context, so that opening links in a new tab or downloading them
+/// keeps working.
+pub(crate) fn is_plain_click(event: &MouseEvent, anchor: &HtmlAnchorElement) -> bool {
+    let target = anchor.target();
+    event.button() == 0
+        && !(event.alt_key() || event.ctrl_key() || event.meta_key() || event.shift_key())
+        && !event.default_prevented()
+        && (target.is_empty() || target.eq_ignore_ascii_case("_self"))
+        && !anchor.has_attribute("download")
+}
+
+/// Returns the route a link navigates to within the app according to `mode`, given the origin,
+/// path and query string of the current page and of the link's URL, and the link's fragment.
+/// Returns `None` for links to other origins or pages, and, in [Mode::History], for links to
+/// fragments of the current page, which the browser scrolls to.
+fn link_route(
+    mode: Mode,
+    page_origin: &str,
+    page_path: &str,
+    link_origin: &str,
+    link_path: &str,
+    link_hash: &str,
+) -> Option<String> {
+    if link_origin != page_origin {
+        return None;
+    }
+    match mode {
+        Mode::History if !link_hash.is_empty() && link_path == page_path => None,
+        Mode::History => Some(format!("{}{}", link_path, link_hash)),
+        Mode::Hash if link_path != page_path => None,
+        Mode::Hash => match link_hash.strip_prefix('#').unwrap_or(link_hash) {
+            "" => Some("/".to_owned()),
+            route => Some(route.to_owned()),
+        },
+    }
+}
+
+/// Navigates to the route of the link clicked by `event`, if it is a plain click on a link within the app.
+fn intercept_click(event: &MouseEvent) {
+    let anchor = event
+        .target()
+        .and_then(|target| target.dyn_into::<Element>().ok())
+        .and_then(|element| element.closest("a[href]").ok().flatten())
+        .and_then(|element| element.dyn_into::<HtmlAnchorElement>().ok());
+    let anchor = match anchor {
+        Some(anchor) if is_plain_click(event, &anchor) => anchor,
+        _ => return,
+    };
+    let page = web_sys::window().unwrap().location();
+    let route = link_route(
+        mode(),
+        &page.origin().unwrap_or_default(),
+        &format!(
+            "{}{}",
+            page.pathname().unwrap_or_default(),
+            page.search().unwrap_or_default()
+        ),
+        &anchor.origin(),
+        &format!("{}{}", anchor.pathname(), anchor.search()),
+        &anchor.hash(),
+    );
+    if let Some(route) = route {
+        event.prevent_default();
+        Navigator::new().push(&route);
+    }
+}
+
+/// Returns the document's click listener navigating within the app, adding it if no `location`
+/// hook holds it. It is removed once no hook holds it.
+fn intercept_links() -> Rc<EventListener> {
+    LINK_INTERCEPTOR.with(|interceptor| {
+        if let Some(listener) = interceptor.borrow().upgrade() {
+            return listener;
+        }
+        let document = web_sys::window().unwrap().document().unwrap();
+        // in the bubble phase on the document, the listener runs after the handlers of the
+        // clicked elements, which may prevent the navigation
+        let listener = Rc::new(EventListener::new(&document, "click", |event| {
+            if let Some(event) = event.dyn_ref::<MouseEvent>() {
+                intercept_click(event);
+            }
+        }));
+        *interceptor.borrow_mut() = Rc::downgrade(&listener);
+        listener
+    })
+}
+
+struct LocationState {
+    location: Location,
+    /// Listeners updating `location`, attached on the first render.
+    listeners: RefCell<Vec<EventListener>>,
+    /// The click listener navigating within the app, shared by every `location` hook.
+    interceptor: RefCell<Option<Rc<EventListener>>>,
+}
+
+impl LocationState {
+    fn current() -> Self {
+        Self {
+            location: Location::current(),
+            listeners: RefCell::new(Vec::new()),
+            interceptor: RefCell::new(None),
+        }
+    }
+}
+
+/// A hook returning the current [Location] and a [Navigator] to change it.
+///
+/// The component calling `location` is rerendered whenever the location changes, whether through a
+/// [Navigator], a link, or the browser's back and forward buttons. While any component calling
+/// `location` is mounted, clicks on plain `A` elements linking to routes within the app navigate
+/// without reloading the page, as described in the [crate] documentation. Like
+/// [state](avalanche::state), `location` must be called with a context from [HookContext::with_key](avalanche::hooks::HookContext::with_key)
+/// within a loop or an iterator closure.
+///
+/// # Example
+/// ```rust
+/// use avalanche::{component, tracked, View};
+/// use avalanche_router::location;
+/// use avalanche_web::components::{Button, Div, Text};
+///
+/// #[component]
+/// fn Search() -> View {
+///     let (location, navigator) = location(self);
+///     let query = tracked!(location).query_param::<String>("q").unwrap_or_default();
+///     Div(self, [
+///         Text(self, format!("Results for {}", tracked!(query))),
+///         Button(
+///             self,
+///             on_click = move |_| navigator.push("/search?q=avalanche"),
+///             Text(self, "Search for avalanche")
+///         )
+///     ])
+/// }
+/// ```
+#[track_caller]
+pub fn location<'a>(ctx: HookContext<'a, '_>) -> (Tracked<&'a Location>, Navigator) {
+    let (state, set_state) = state(ctx, LocationState::current);
+
+    let mut listeners = tracked!(state).listeners.borrow_mut();
+    if listeners.is_empty() {
+        let window = web_sys::window().unwrap();
+        for event in ["popstate", NAVIGATE_EVENT] {
+            let set_state = set_state.clone();
+            listeners.push(EventListener::new(&window, event, move |_| {
+                set_state.update(|state| state.location = Location::current())
+            }));
+        }
+        *tracked!(state).interceptor.borrow_mut() = Some(intercept_links());
+    }
+
+    (state.map(|state| &state.location), Navigator::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing() {
+        let location = Location::parse("/users/42?tab=posts#comments");
+        assert_eq!(location.path(), "/users/42");
+        assert_eq!(location.query(), "tab=posts");
+
+        let location = Location::parse("/about#team?x=1");
+        assert_eq!(location.path(), "/about");
+        assert_eq!(location.query(), "");
+
+        assert_eq!(Location::parse(""), Location::parse("/"));
+        assert_eq!(Location::parse("?q=1").path(), "/");
+        assert_eq!(Location::parse("/a?").query(), "");
+    }
+
+    #[test]
+    fn query_params() {
+        let location = Location::parse("/search?q=rust+web&page=2&&flag&tag=a&tag=b");
+        assert_eq!(
+            location.query_params().collect::<Vec<_>>(),
+            [
+                ("q".to_owned(), "rust web".to_owned()),
+                ("page".to_owned(), "2".to_owned()),
+                ("flag".to_owned(), "".to_owned()),
+                ("tag".to_owned(), "a".to_owned()),
+                ("tag".to_owned(), "b".to_owned()),
+            ]
+        );
+        assert_eq!(location.query_param::<u32>("page"), Some(2));
+        assert_eq!(location.query_param::<u32>("q"), None);
+        assert_eq!(location.query_param::<String>("missing"), None);
+        // the first of repeated parameters is returned
+        assert_eq!(location.query_param::<String>("tag").as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn link_routes() {
+        let route = |mode, link_origin, link_path, link_hash| {
+            link_route(mode, "https://a.com", "/users?page=2", link_origin, link_path, link_hash)
+        };
+        assert_eq!(route(Mode::History, "https://a.com", "/about", "").as_deref(), Some("/about"));
+        assert_eq!(
+            route(Mode::History, "https://a.com", "/users", "#top").as_deref(),
+            Some("/users#top")
+        );
+        // fragments of the current page are left to the browser
+        assert_eq!(route(Mode::History, "https://a.com", "/users?page=2", "#top"), None);
+        assert_eq!(route(Mode::History, "https://b.com", "/about", ""), None);
+        assert_eq!(route(Mode::History, "http://a.com", "/about", ""), None);
+
+        assert_eq!(
+            route(Mode::Hash, "https://a.com", "/users?page=2", "#/about?x=1").as_deref(),
+            Some("/about?x=1")
+        );
+        assert_eq!(route(Mode::Hash, "https://a.com", "/users?page=2", "").as_deref(), Some("/"));
+        // in hash mode, routes are only within the current page
+        assert_eq!(route(Mode::Hash, "https://a.com", "/about", "#/about"), None);
+        assert_eq!(route(Mode::Hash, "https://b.com", "/users?page=2", "#/about"), None);
+    }
+
+    #[test]
+    fn encoded_query_params() {
+        let location = Location::parse("/?first%20name=J%C3%BCrgen&a%3Db=c%26d&x=1%2B1");
+        assert_eq!(
+            location.query_param::<String>("first name").as_deref(),
+            Some("Jürgen")
+        );
+        assert_eq!(location.query_param::<String>("a=b").as_deref(), Some("c&d"));
+        assert_eq!(location.query_param::<String>("x").as_deref(), Some("1+1"));
+    }
+}
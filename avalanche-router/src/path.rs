@@ -0,0 +1,147 @@
+use std::str::FromStr;
+
+/// The parameters captured by matching a path against a pattern, returned by [match_path].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params {
+    params: Vec<(String, String)>,
+    rest: Option<String>,
+}
+
+impl Params {
+    /// Returns the path segment captured by the parameter `name`, parsed as `T`, or `None` if the
+    /// pattern has no such parameter or the segment fails to parse.
+    pub fn get<T: FromStr>(&self, name: &str) -> Option<T> {
+        self.get_str(name)?.parse().ok()
+    }
+
+    /// Returns the path segment captured by the parameter `name`, percent-decoded.
+    pub fn get_str(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the part of the path matched by a trailing `*` in the pattern, starting with `/`.
+    /// Nested routes are matched against this rest of the path.
+    ///
+    /// Returns `/` if the pattern has no `*`, or the `*` matched no segments.
+    pub fn rest(&self) -> &str {
+        self.rest.as_deref().unwrap_or("/")
+    }
+}
+
+/// Matches `path` against `pattern`, returning the captured parameters if it matches.
+///
+/// Patterns are made of `/`-separated segments, each of which is either:
+/// - a literal, like `users`, matching only a segment equal to it;
+/// - a parameter, like `:id`, matching any one segment, which is captured under the name `id`;
+/// - a wildcard `*`, allowed only as the last segment, matching the rest of the path.
+///
+/// Empty segments are ignored, so `/users/` matches the pattern `/users`.
+///
+/// # Example
+/// ```rust
+/// use avalanche_router::match_path;
+///
+/// let params = match_path("/users/:id/*", "/users/42/posts/7").unwrap();
+/// assert_eq!(params.get::<u32>("id"), Some(42));
+/// assert_eq!(params.rest(), "/posts/7");
+///
+/// // nested routes match against the rest of the path
+/// let post = match_path("/posts/:post", params.rest()).unwrap();
+/// assert_eq!(post.get::<u32>("post"), Some(7));
+///
+/// assert!(match_path("/users/:id", "/users").is_none());
+/// ```
+pub fn match_path(pattern: &str, path: &str) -> Option<Params> {
+    let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+    let mut params = Params::default();
+
+    for pattern_segment in pattern.split('/').filter(|segment| !segment.is_empty()) {
+        if pattern_segment == "*" {
+            let rest: Vec<&str> = segments.collect();
+            params.rest = Some(format!("/{}", rest.join("/")));
+            return Some(params);
+        }
+        let segment = segments.next()?;
+        match pattern_segment.strip_prefix(':') {
+            Some(name) => params.params.push((name.to_owned(), decode(segment, false))),
+            None => {
+                if pattern_segment != segment {
+                    return None;
+                }
+            }
+        }
+    }
+
+    // every segment of the path must have been matched
+    match segments.next() {
+        Some(_) => None,
+        None => Some(params),
+    }
+}
+
+/// Decodes the percent-encoded `string`, also decoding `+` as a space if `plus_as_space` is true,
+/// as within query strings. Invalid escapes are left as they are.
+pub(crate) fn decode(string: &str, plus_as_space: bool) -> String {
+    let bytes = string.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 3;
+                        continue;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            b'+' if plus_as_space => decoded.push(b' '),
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literals_and_params() {
+        assert!(match_path("/", "/").is_some());
+        assert!(match_path("/about", "/about/").is_some());
+        assert!(match_path("/about", "/").is_none());
+        assert!(match_path("/about", "/about/team").is_none());
+
+        let params = match_path("/users/:name/posts/:id", "/users/J%C3%BCrgen/posts/12").unwrap();
+        assert_eq!(params.get_str("name"), Some("Jürgen"));
+        assert_eq!(params.get::<u32>("id"), Some(12));
+        assert_eq!(params.get::<u32>("name"), None);
+        assert_eq!(params.get_str("missing"), None);
+    }
+
+    #[test]
+    fn wildcard() {
+        assert_eq!(match_path("/settings/*", "/settings").unwrap().rest(), "/");
+        assert_eq!(
+            match_path("/settings/*", "/settings/profile/email").unwrap().rest(),
+            "/profile/email"
+        );
+        assert!(match_path("/settings/*", "/profile").is_none());
+    }
+
+    #[test]
+    fn decoding() {
+        assert_eq!(decode("a%20b+c", false), "a b+c");
+        assert_eq!(decode("a%20b+c", true), "a b c");
+        assert_eq!(decode("100%", false), "100%");
+        assert_eq!(decode("%zz%4", false), "%zz%4");
+    }
+}
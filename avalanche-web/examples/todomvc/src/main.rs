@@ -20,16 +20,6 @@ enum Filter {
     Completed,
 }
 
-impl Filter {
-    fn selected(&self, other: Filter) -> &'static str {
-        if *self == other {
-            "selected"
-        } else {
-            ""
-        }
-    }
-}
-
 #[component]
 fn Todo() -> View {
     let (editing, set_editing) = state::<Option<u32>>(self, || None);
@@ -177,7 +167,7 @@ fn Todo() -> View {
                                                 self,
                                                 A(
                                                     self,
-                                                    class = tracked!(filter).selected(Filter::All),
+                                                    classes = [("selected", *tracked!(filter) == Filter::All)],
                                                     href = "#/",
                                                     on_click = move |_| {
                                                         set_filter.set(Filter::All);
@@ -189,8 +179,8 @@ fn Todo() -> View {
                                                 self,
                                                 A(
                                                     self,
-                                                    class =
-                                                        tracked!(filter).selected(Filter::Active),
+                                                    classes =
+                                                        [("selected", *tracked!(filter) == Filter::Active)],
                                                     href = "#/active",
                                                     on_click = move |_| {
                                                         set_filter.set(Filter::Active);
@@ -202,8 +192,10 @@ fn Todo() -> View {
                                                 self,
                                                 A(
                                                     self,
-                                                    class = tracked!(filter)
-                                                        .selected(Filter::Completed),
+                                                    classes = [(
+                                                        "selected",
+                                                        *tracked!(filter) == Filter::Completed
+                                                    )],
                                                     href = "#/completed",
                                                     on_click = move |_| {
                                                         set_filter.set(Filter::Completed);
@@ -243,15 +235,10 @@ fn TodoItem(
 ) -> View {
     Li(
         self,
-        class = format!(
-            "{} {}",
-            if tracked!(item).completed {
-                "completed"
-            } else {
-                ""
-            },
-            if tracked!(is_editing) { "editing" } else { "" }
-        ),
+        classes = [
+            ("completed", tracked!(item).completed),
+            ("editing", tracked!(is_editing)),
+        ],
         [
             Div(
                 self,
@@ -4,6 +4,10 @@ export function clear_string_cache() {
     stringCache.length = 1;
 }
 
+export function queue_microtask(callback) {
+    queueMicrotask(callback);
+}
+
 const stringChunkSize = 1024;
 export function intern_string_at(string, pos) {
     let result = '';
@@ -13,24 +17,100 @@ export function intern_string_at(string, pos) {
     stringCache[pos] = result;
 }
 
-export function append_child(parent, child) {
+function append_child(parent, child) {
     parent.append(child);
+    syncOptions(parent);
 }
 
-export function insert_child(parent, idx, child) {
+function insert_child(parent, idx, child) {
     const afterIdx = parent.childNodes.item(idx);
     parent.insertBefore(child, afterIdx);
+    syncOptions(parent);
 }
 
-export function swap_children(parent, lesserIdx, greaterIdx) {
+// Swaps children, moving as few nodes as possible. Moving a node with insertBefore detaches it,
+// dropping its focus and selection and reloading embedded content, so nodes holding such state are
+// kept in place, or moved with moveBefore where supported, which keeps their state.
+function swap_children(parent, lesserIdx, greaterIdx) {
     const lesser = parent.childNodes.item(lesserIdx);
     const greater = parent.childNodes.item(greaterIdx);
-    const afterGreater = greater.nextSibling;
-    parent.replaceChild(greater, lesser);
-    parent.insertBefore(lesser, afterGreater);
+    const active = document.activeElement;
+    const selection = active !== null && active.selectionStart != null
+        ? [active.selectionStart, active.selectionEnd, active.selectionDirection]
+        : null;
+
+    if (lesser.nextSibling === greater) {
+        // adjacent children only need one of them moved
+        if (isStateful(greater, active)) {
+            parent.insertBefore(lesser, greater.nextSibling);
+        } else {
+            parent.insertBefore(greater, lesser);
+        }
+    } else {
+        const afterLesser = lesser.nextSibling;
+        const afterGreater = greater.nextSibling;
+        const lesserStateful = isStateful(lesser, active);
+        const greaterStateful = isStateful(greater, active);
+        if (lesserStateful === greaterStateful) {
+            parent.insertBefore(greater, lesser);
+            parent.insertBefore(lesser, afterGreater);
+        } else if (typeof parent.moveBefore === 'function') {
+            // move the other child next to the stateful one's sibling, then the stateful one
+            // without detaching it
+            if (lesserStateful) {
+                parent.insertBefore(greater, afterLesser);
+                parent.moveBefore(lesser, afterGreater);
+            } else {
+                parent.insertBefore(lesser, afterGreater);
+                parent.moveBefore(greater, afterLesser);
+            }
+        } else {
+            const between = [];
+            for (let node = afterLesser; node !== greater; node = node.nextSibling) {
+                between.push(node);
+            }
+            if (between.some(node => isStateful(node, active))) {
+                // moving the children between would reset them too, so move both swapped children,
+                // restoring focus and selection below
+                parent.insertBefore(greater, lesser);
+                parent.insertBefore(lesser, afterGreater);
+            } else {
+                // keep the stateful child in place by moving the other one and the children
+                // between them
+                if (lesserStateful) {
+                    parent.insertBefore(greater, lesser);
+                } else {
+                    parent.insertBefore(lesser, afterGreater);
+                }
+                for (const node of between) {
+                    parent.insertBefore(node, lesser);
+                }
+            }
+        }
+    }
+
+    // restore focus and selection if the focused element had to be moved anyway
+    if (active !== null && active !== document.activeElement && active.isConnected) {
+        active.focus({ preventScroll: true });
+        if (selection !== null) {
+            active.setSelectionRange(...selection);
+        }
+    }
+    syncOptions(parent);
 }
 
-export function truncate_children(parent, len) {
+const STATEFUL_SELECTOR = 'iframe, video, audio, object, embed';
+
+// Whether node contains the focused element or embedded content that moving it would reset
+function isStateful(node, active) {
+    if (active !== null && active !== document.body && node.contains(active)) {
+        return true;
+    }
+    return node.nodeType === Node.ELEMENT_NODE
+        && (node.matches(STATEFUL_SELECTOR) || node.querySelector(STATEFUL_SELECTOR) !== null);
+}
+
+function truncate_children(parent, len) {
     if (len === 0) {
         parent.innerHTML = '';
         return;
@@ -40,25 +120,125 @@ export function truncate_children(parent, len) {
     }
 }
 
-export function create_text_node(strIdx) {
-    return document.createTextNode(stringCache[strIdx]);
+// Nodes referred to by buffered commands, indexed by their ids
+const nodes = [];
+
+export function set_node(id, node) {
+    nodes[id] = node;
+}
+
+export function create_text_node(id, strIdx) {
+    return nodes[id] = document.createTextNode(stringCache[strIdx]);
 }
 
+export function create_element(id, strIdx) {
+    return nodes[id] = document.createElement(stringCache[strIdx]);
+}
 
-export function create_element(strIdx) {
-    return document.createElement(stringCache[strIdx]);
+export function create_element_ns(id, namespaceIdx, tagIdx) {
+    return nodes[id] = document.createElementNS(stringCache[namespaceIdx], stringCache[tagIdx]);
 }
 
-export function set_text_content(textNode, strIdx) {
+// Op codes of buffered commands, in the order of `Op` in commands.rs
+const APPEND_CHILD = 0;
+const INSERT_CHILD = 1;
+const SWAP_CHILDREN = 2;
+const TRUNCATE_CHILDREN = 3;
+const SET_TEXT_CONTENT = 4;
+const SET_ATTRIBUTE = 5;
+const SET_ATTRIBUTE_NS = 6;
+const SET_STYLE_PROPERTY = 7;
+const REMOVE_STYLE_PROPERTY = 8;
+const ADD_CLASS = 9;
+const REMOVE_CLASS = 10;
+const SET_SELECT_VALUES = 11;
+const RELEASE_NODE = 12;
+const RELEASE_STRING = 13;
+
+// Applies a buffer of commands, each an op code followed by its operands
+export function flush(buffer) {
+    // buffer views wasm memory, which listeners fired by the changes could grow and detach
+    const commands = buffer.slice();
+    let i = 0;
+    while (i < commands.length) {
+        switch (commands[i++]) {
+            case APPEND_CHILD:
+                append_child(nodes[commands[i]], nodes[commands[i + 1]]);
+                i += 2;
+                break;
+            case INSERT_CHILD:
+                insert_child(nodes[commands[i]], commands[i + 1], nodes[commands[i + 2]]);
+                i += 3;
+                break;
+            case SWAP_CHILDREN:
+                swap_children(nodes[commands[i]], commands[i + 1], commands[i + 2]);
+                i += 3;
+                break;
+            case TRUNCATE_CHILDREN:
+                truncate_children(nodes[commands[i]], commands[i + 1]);
+                i += 2;
+                break;
+            case SET_TEXT_CONTENT:
+                set_text_content(nodes[commands[i]], commands[i + 1]);
+                i += 2;
+                break;
+            case SET_ATTRIBUTE:
+                set_attribute(nodes[commands[i]], commands[i + 1], commands[i + 2]);
+                i += 3;
+                break;
+            case SET_ATTRIBUTE_NS:
+                set_attribute_ns(nodes[commands[i]], commands[i + 1], commands[i + 2], commands[i + 3]);
+                i += 4;
+                break;
+            case SET_STYLE_PROPERTY:
+                set_style_property(nodes[commands[i]], commands[i + 1], commands[i + 2]);
+                i += 3;
+                break;
+            case REMOVE_STYLE_PROPERTY:
+                remove_style_property(nodes[commands[i]], commands[i + 1]);
+                i += 2;
+                break;
+            case ADD_CLASS:
+                add_class(nodes[commands[i]], commands[i + 1]);
+                i += 2;
+                break;
+            case REMOVE_CLASS:
+                remove_class(nodes[commands[i]], commands[i + 1]);
+                i += 2;
+                break;
+            case SET_SELECT_VALUES: {
+                const count = commands[i + 1];
+                set_select_values(nodes[commands[i]], commands.subarray(i + 2, i + 2 + count));
+                i += 2 + count;
+                break;
+            }
+            case RELEASE_NODE:
+                nodes[commands[i]] = undefined;
+                i += 1;
+                break;
+            case RELEASE_STRING:
+                stringCache[commands[i]] = undefined;
+                i += 1;
+                break;
+            default:
+                throw new Error(`unknown command ${commands[i - 1]}`);
+        }
+    }
+}
+
+function set_text_content(textNode, strIdx) {
     textNode.textContent = stringCache[strIdx];
 }
 
-export function set_attribute(element, nameIdx, valueIdx) {
+function set_attribute(element, nameIdx, valueIdx) {
     const name = stringCache[nameIdx];
     const value = stringCache[valueIdx];
     switch (name) {
         case 'value':
-            element.value = value;
+            setValue(element, value);
+            if (element.tagName === 'OPTION') {
+                syncOptions(element.parentNode);
+            }
             break;
         case 'checked':
             element.checked = value !== '';
@@ -71,3 +251,137 @@ export function set_attribute(element, nameIdx, valueIdx) {
             }
     }
 }
+
+// Sets up a controlled input or textarea, whose value is not changed during IME composition
+export function control_value(element) {
+    element.addEventListener('compositionstart', () => {
+        element.__avalancheComposing = true;
+    });
+    element.addEventListener('compositionend', () => {
+        element.__avalancheComposing = false;
+        const pending = element.__avalanchePendingValue;
+        if (pending !== undefined) {
+            element.__avalanchePendingValue = undefined;
+            setValue(element, pending);
+        }
+    });
+}
+
+// Sets the value of element, preserving the caret and selection of a focused element
+function setValue(element, value) {
+    if (element.__avalancheComposing) {
+        // changing the value would cancel the composition, so it's applied once composition ends
+        element.__avalanchePendingValue = value;
+        return;
+    }
+    const oldValue = element.value;
+    if (oldValue === value) {
+        return;
+    }
+    if (document.activeElement !== element || element.selectionStart == null) {
+        element.value = value;
+        return;
+    }
+    let start = element.selectionStart;
+    let end = element.selectionEnd;
+    const direction = element.selectionDirection;
+    element.value = value;
+    if (value.length !== oldValue.length) {
+        // keep the selection at the same distance from the end, where most edits leave it
+        start = Math.max(0, value.length - (oldValue.length - start));
+        end = Math.max(0, value.length - (oldValue.length - end));
+    }
+    element.setSelectionRange(start, end, direction);
+}
+
+function set_attribute_ns(element, namespaceIdx, nameIdx, valueIdx) {
+    const namespace = stringCache[namespaceIdx];
+    const name = stringCache[nameIdx];
+    const value = stringCache[valueIdx];
+    if (value === '') {
+        // removeAttributeNS takes the local name, without the prefix
+        element.removeAttributeNS(namespace, name.slice(name.indexOf(':') + 1));
+    } else {
+        element.setAttributeNS(namespace, name, value);
+    }
+}
+
+function set_style_property(element, nameIdx, valueIdx) {
+    element.style.setProperty(stringCache[nameIdx], stringCache[valueIdx]);
+}
+
+function remove_style_property(element, nameIdx) {
+    element.style.removeProperty(stringCache[nameIdx]);
+}
+
+function add_class(element, nameIdx) {
+    element.classList.add(stringCache[nameIdx]);
+}
+
+function remove_class(element, nameIdx) {
+    element.classList.remove(stringCache[nameIdx]);
+}
+
+// Controlled selects store their values, so options added or changed later are selected to match
+function set_select_values(select, valueIdxs) {
+    select.__avalancheValues = Array.from(valueIdxs, idx => stringCache[idx]);
+    sync_select(select);
+}
+
+export function sync_select(select) {
+    const values = select.__avalancheValues;
+    for (const option of select.options) {
+        option.selected = values.includes(option.value);
+    }
+}
+
+// Resyncs the controlled select containing parent, if any, after its options change
+function syncOptions(parent) {
+    if (parent === null) {
+        return;
+    }
+    const select = parent.tagName === 'OPTGROUP' ? parent.parentNode : parent;
+    if (select !== null && select.__avalancheValues !== undefined) {
+        sync_select(select);
+    }
+}
+
+export function set_delegate_id(node, id) {
+    node.__avalancheId = id;
+}
+
+// Returns the delegate ids of the nodes from target up to, but excluding, root
+export function delegate_path(target, root) {
+    const ids = [];
+    for (let node = target; node !== null && node !== root; node = node.parentNode) {
+        const id = node.__avalancheId;
+        if (id !== undefined) {
+            ids.push(id);
+        }
+    }
+    return new Uint32Array(ids);
+}
+
+// Observers call `callback` with two numbers for the latest entry, and are stopped with `disconnect`.
+
+export function observe_resize(element, callback) {
+    const observer = new ResizeObserver(entries => {
+        const rect = entries[entries.length - 1].contentRect;
+        callback(rect.width, rect.height);
+    });
+    observer.observe(element);
+    return observer;
+}
+
+export function observe_intersection(element, thresholds, callback) {
+    const observer = new IntersectionObserver(entries => {
+        const entry = entries[entries.length - 1];
+        callback(entry.isIntersecting ? 1 : 0, entry.intersectionRatio);
+    }, { threshold: Array.from(thresholds) });
+    observer.observe(element);
+    return observer;
+}
+
+export function disconnect(observer) {
+    observer.disconnect();
+}
@@ -1,17 +1,32 @@
+use wasm_bindgen::closure::Closure;
 use wasm_bindgen::prelude::{wasm_bindgen, JsValue};
 
 #[wasm_bindgen(module = "/js/bridge.js")]
 extern "C" {
-    pub(crate) fn append_child(parent: &JsValue, child: &JsValue);
-    pub(crate) fn insert_child(parent: &JsValue, idx: u32, child: &JsValue);
-    pub(crate) fn swap_children(parent: &JsValue, lesser_idx: u32, greater_idx: u32);
-    pub(crate) fn truncate_children(parent: &JsValue, len: u32);
+    pub(crate) fn flush(commands: &[u32]);
+    
+    pub(crate) fn queue_microtask(callback: &Closure<dyn FnMut()>);
+    #[wasm_bindgen(js_name = queue_microtask)]
+    pub(crate) fn queue_microtask_once(callback: JsValue);
     
     pub(crate) fn intern_string_at(string: &[u16], idx: u32);
     
-    pub(crate) fn create_text_node(value_idx: u32) -> JsValue;
-    pub(crate) fn create_element(tag_idx: u32) -> JsValue;
+    pub(crate) fn set_node(id: u32, node: &JsValue);
+    pub(crate) fn create_text_node(id: u32, value_idx: u32) -> JsValue;
+    pub(crate) fn create_element(id: u32, tag_idx: u32) -> JsValue;
+    pub(crate) fn create_element_ns(id: u32, namespace_idx: u32, tag_idx: u32) -> JsValue;
+    
+    pub(crate) fn control_value(element: &JsValue);
+    pub(crate) fn sync_select(select: &JsValue);
+    
+    pub(crate) fn set_delegate_id(node: &JsValue, id: u32);
+    pub(crate) fn delegate_path(target: &JsValue, root: &JsValue) -> Vec<u32>;
     
-    pub(crate) fn set_text_content(text_node: &JsValue, value_idx: u32);
-    pub(crate) fn set_attribute(element: &JsValue, name_idx: u32, value_idx: u32);
+    pub(crate) fn observe_resize(element: &JsValue, callback: &Closure<dyn FnMut(f64, f64)>) -> JsValue;
+    pub(crate) fn observe_intersection(
+        element: &JsValue,
+        thresholds: &[f64],
+        callback: &Closure<dyn FnMut(f64, f64)>,
+    ) -> JsValue;
+    pub(crate) fn disconnect(observer: &JsValue);
 }
\ No newline at end of file
@@ -0,0 +1,123 @@
+//! A list of toggleable classes, passed to an element's `classes` parameter.
+//!
+//! Classes are added and removed individually through the element's `classList`,
+//! so toggling one class leaves the others untouched:
+//!
+//! ```rust
+//! use avalanche::{component, tracked, state, View};
+//! use avalanche_web::components::{Li, Text};
+//!
+//! #[component]
+//! fn Item() -> View {
+//!     let (completed, _) = state(self, || false);
+//!     let (editing, _) = state(self, || false);
+//!     Li(
+//!         self,
+//!         classes = [
+//!             ("item", true),
+//!             ("completed", *tracked!(completed)),
+//!             ("editing", *tracked!(editing)),
+//!         ],
+//!         Text(self, "item")
+//!     )
+//! }
+//! ```
+//!
+//! A static `class` attribute may be combined with `classes`, but changing the `class` attribute
+//! on a later render replaces every class, including those added through `classes`.
+
+use std::borrow::Cow;
+
+/// A list of classes, each either enabled or disabled.
+///
+/// A `ClassList` can be built with [class](ClassList::class) and [toggle](ClassList::toggle),
+/// or converted from an array or iterator of `(class, enabled)` pairs. If a class appears more than once,
+/// its last entry decides whether it is enabled.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ClassList<'a> {
+    pub(crate) classes: Vec<(Cow<'a, str>, bool)>,
+}
+
+impl<'a> ClassList<'a> {
+    /// Creates an empty class list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables the class `name`.
+    pub fn class(self, name: impl Into<Cow<'a, str>>) -> Self {
+        self.toggle(name, true)
+    }
+
+    /// Enables the class `name` if `enabled` is true.
+    pub fn toggle(mut self, name: impl Into<Cow<'a, str>>, enabled: bool) -> Self {
+        self.classes.push((name.into(), enabled));
+        self
+    }
+
+    /// Returns an iterator over the names of the enabled classes.
+    pub fn enabled(&self) -> impl Iterator<Item = &str> {
+        let mut enabled: Vec<&str> = Vec::new();
+        for (name, is_enabled) in &self.classes {
+            enabled.retain(|class| class != name);
+            if *is_enabled {
+                enabled.push(name);
+            }
+        }
+        enabled.into_iter()
+    }
+}
+
+impl<'a, C: Into<Cow<'a, str>>> FromIterator<(C, bool)> for ClassList<'a> {
+    fn from_iter<I: IntoIterator<Item = (C, bool)>>(iter: I) -> Self {
+        Self {
+            classes: iter
+                .into_iter()
+                .map(|(name, enabled)| (name.into(), enabled))
+                .collect(),
+        }
+    }
+}
+
+impl<'a, C: Into<Cow<'a, str>>, const N: usize> From<[(C, bool); N]> for ClassList<'a> {
+    fn from(classes: [(C, bool); N]) -> Self {
+        classes.into_iter().collect()
+    }
+}
+
+impl<'a, C: Into<Cow<'a, str>>> From<Vec<(C, bool)>> for ClassList<'a> {
+    fn from(classes: Vec<(C, bool)>) -> Self {
+        classes.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled<'a>(classes: &'a ClassList) -> Vec<&'a str> {
+        classes.enabled().collect()
+    }
+
+    #[test]
+    fn enabled_classes() {
+        let classes = ClassList::new()
+            .class("item")
+            .toggle("completed", false)
+            .toggle("editing", true);
+        assert_eq!(enabled(&classes), ["item", "editing"]);
+        assert!(ClassList::new().enabled().next().is_none());
+    }
+
+    #[test]
+    fn last_entry_wins() {
+        let classes = ClassList::from([("a", true), ("b", true), ("a", true)]);
+        assert_eq!(enabled(&classes), ["b", "a"]);
+
+        let classes = ClassList::from([("a", true), ("b", true), ("a", false)]);
+        assert_eq!(enabled(&classes), ["b"]);
+
+        let classes = ClassList::new().toggle("a", false).class("b").class("a");
+        assert_eq!(enabled(&classes), ["b", "a"]);
+    }
+}
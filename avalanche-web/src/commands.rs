@@ -0,0 +1,216 @@
+//! Buffering of DOM mutations: instead of calling into JS for every change, changes are
+//! encoded into a buffer of `u32`s and applied by JS in a single call once a render completes.
+//!
+//! Nodes are referred to by ids into a table on the JS side, and strings by their indices
+//! in the bridge string cache.
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use crate::bridge;
+
+/// The commands understood by `flush` in `bridge.js`. Each is followed by its operands.
+#[derive(Copy, Clone)]
+#[repr(u32)]
+enum Op {
+    /// `parent`, `child`
+    AppendChild,
+    /// `parent`, `index`, `child`
+    InsertChild,
+    /// `parent`, `lesser_index`, `greater_index`
+    SwapChildren,
+    /// `parent`, `len`
+    TruncateChildren,
+    /// `node`, `value`
+    SetTextContent,
+    /// `element`, `name`, `value`
+    SetAttribute,
+    /// `element`, `namespace`, `name`, `value`
+    SetAttributeNs,
+    /// `element`, `name`, `value`
+    SetStyleProperty,
+    /// `element`, `name`
+    RemoveStyleProperty,
+    /// `element`, `name`
+    AddClass,
+    /// `element`, `name`
+    RemoveClass,
+    /// `select`, `count`, followed by `count` values
+    SetSelectValues,
+    /// `node`
+    ReleaseNode,
+    /// `string`
+    ReleaseString,
+}
+
+struct CommandBufferState {
+    commands: Vec<u32>,
+    /// Ids released since the last flush. They are only reused after the next flush,
+    /// as buffered commands may still refer to them.
+    released: Vec<u32>,
+    free: Vec<u32>,
+    next_id: u32,
+    /// The number of commands pushed so far.
+    pushed: u64,
+}
+
+/// A buffer of DOM mutations, applied by [flush](CommandBuffer::flush).
+#[derive(Clone)]
+pub(crate) struct CommandBuffer {
+    state: Rc<RefCell<CommandBufferState>>,
+}
+
+impl CommandBuffer {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: Rc::new(RefCell::new(CommandBufferState {
+                commands: Vec::new(),
+                released: Vec::new(),
+                free: Vec::new(),
+                next_id: 0,
+                pushed: 0,
+            })),
+        }
+    }
+
+    /// Allocates an id for a node. The node is removed from the JS node table once the id is dropped.
+    pub(crate) fn node_id(&self) -> NodeId {
+        let mut state = self.state.borrow_mut();
+        let id = match state.free.pop() {
+            Some(id) => id,
+            None => {
+                state.next_id += 1;
+                state.next_id - 1
+            }
+        };
+        NodeId {
+            state: Rc::downgrade(&self.state),
+            id,
+        }
+    }
+
+    fn push(&self, op: Op, operands: &[u32]) {
+        let mut state = self.state.borrow_mut();
+        state.commands.push(op as u32);
+        state.commands.extend_from_slice(operands);
+        state.pushed += 1;
+    }
+
+    /// Returns the number of commands pushed so far, which changes whenever a command is pushed.
+    pub(crate) fn pushed(&self) -> u64 {
+        self.state.borrow().pushed
+    }
+
+    pub(crate) fn append_child(&self, parent: u32, child: u32) {
+        self.push(Op::AppendChild, &[parent, child]);
+    }
+
+    pub(crate) fn insert_child(&self, parent: u32, index: u32, child: u32) {
+        self.push(Op::InsertChild, &[parent, index, child]);
+    }
+
+    pub(crate) fn swap_children(&self, parent: u32, lesser_index: u32, greater_index: u32) {
+        self.push(Op::SwapChildren, &[parent, lesser_index, greater_index]);
+    }
+
+    pub(crate) fn truncate_children(&self, parent: u32, len: u32) {
+        self.push(Op::TruncateChildren, &[parent, len]);
+    }
+
+    pub(crate) fn set_text_content(&self, node: u32, value_idx: u32) {
+        self.push(Op::SetTextContent, &[node, value_idx]);
+    }
+
+    pub(crate) fn set_attribute(&self, element: u32, name_idx: u32, value_idx: u32) {
+        self.push(Op::SetAttribute, &[element, name_idx, value_idx]);
+    }
+
+    pub(crate) fn set_attribute_ns(
+        &self,
+        element: u32,
+        namespace_idx: u32,
+        name_idx: u32,
+        value_idx: u32,
+    ) {
+        self.push(
+            Op::SetAttributeNs,
+            &[element, namespace_idx, name_idx, value_idx],
+        );
+    }
+
+    pub(crate) fn set_style_property(&self, element: u32, name_idx: u32, value_idx: u32) {
+        self.push(Op::SetStyleProperty, &[element, name_idx, value_idx]);
+    }
+
+    pub(crate) fn remove_style_property(&self, element: u32, name_idx: u32) {
+        self.push(Op::RemoveStyleProperty, &[element, name_idx]);
+    }
+
+    pub(crate) fn add_class(&self, element: u32, name_idx: u32) {
+        self.push(Op::AddClass, &[element, name_idx]);
+    }
+
+    pub(crate) fn remove_class(&self, element: u32, name_idx: u32) {
+        self.push(Op::RemoveClass, &[element, name_idx]);
+    }
+
+    pub(crate) fn set_select_values(&self, select: u32, value_idxs: &[u32]) {
+        self.push(Op::SetSelectValues, &[select, value_idxs.len() as u32]);
+        self.state
+            .borrow_mut()
+            .commands
+            .extend_from_slice(value_idxs);
+    }
+
+    /// Removes the string at `idx` from the JS string table once the preceding commands are applied.
+    pub(crate) fn release_string(&self, idx: u32) {
+        self.push(Op::ReleaseString, &[idx]);
+    }
+
+    /// Applies the buffered commands in a single call into JS.
+    pub(crate) fn flush(&self) {
+        // The buffer is taken out while JS runs, since listeners fired by the changes,
+        // like blur listeners on a removed element, may buffer commands of their own.
+        let (mut commands, mut released) = {
+            let mut state = self.state.borrow_mut();
+            (
+                std::mem::take(&mut state.commands),
+                std::mem::take(&mut state.released),
+            )
+        };
+        if !commands.is_empty() {
+            bridge::flush(&commands);
+        }
+        commands.clear();
+
+        let mut state = self.state.borrow_mut();
+        if state.commands.is_empty() {
+            // reuse the allocation
+            state.commands = commands;
+        }
+        state.free.append(&mut released);
+    }
+}
+
+/// The id of a node in the JS node table, released once dropped.
+pub(crate) struct NodeId {
+    state: Weak<RefCell<CommandBufferState>>,
+    id: u32,
+}
+
+impl NodeId {
+    pub(crate) fn get(&self) -> u32 {
+        self.id
+    }
+}
+
+impl Drop for NodeId {
+    fn drop(&mut self) {
+        if let Some(state) = self.state.upgrade() {
+            let mut state = state.borrow_mut();
+            state.commands.push(Op::ReleaseNode as u32);
+            state.commands.push(self.id);
+            state.released.push(self.id);
+        }
+    }
+}
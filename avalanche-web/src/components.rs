@@ -2,15 +2,19 @@ use std::borrow::Cow;
 use std::fmt::Display;
 use std::marker::PhantomData;
 use std::cmp::max;
+use std::cell::RefCell;
+use std::rc::Rc;
 
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 use wasm_bindgen::JsCast;
-use web_sys::Element;
 
-use crate::{events::*, WebNativeEvent, WebNativeHandle, WebRenderer, add_named_listener, add_listener, create_handler};
+use crate::node_ref::RawNodeRef;
+use crate::class_list::ClassList;
+use crate::style::Style;
+use crate::{events::*, Listeners, WebNativeEvent, WebNativeHandle, WebRenderer, add_listener};
 use avalanche::{Component, View};
-use avalanche::renderer::{Renderer, NativeHandle, NativeEvent, DispatchNativeEvent};
+use avalanche::renderer::{Renderer, NativeHandle, NativeEvent, DispatchNativeEvent, Priority};
 use avalanche::tracked::Gen;
 use avalanche::hooks::{HookContext, RenderContext};
 use avalanche::alloc::{Bump, Vec as BumpVec, CollectIn};
@@ -66,11 +70,16 @@ impl<'a> Component<'a> for TextImpl<'a> {
     
     fn native_create(&self, renderer: &mut dyn Renderer, _dispatch_native_event: DispatchNativeEvent) -> NativeHandle {
         let renderer = renderer.downcast_mut::<WebRenderer>().unwrap();
-        let text_idx = renderer.string_idx(&self.text);
-        let text_node = super::bridge::create_text_node(text_idx);
+        let (id, text_node) = renderer.create_text_node(&self.text);
         Box::new(WebNativeHandle {
-            node: text_node.unchecked_into(),
-            _listeners: FxHashMap::default(),
+            id,
+            node: text_node.into(),
+            attributes: Default::default(),
+            listeners: Default::default(),
+            node_ref: Default::default(),
+            styles: Default::default(),
+            classes: Default::default(),
+            text: RefCell::new(self.text.to_string()),
         })
     }
 
@@ -83,9 +92,14 @@ impl<'a> Component<'a> for TextImpl<'a> {
     ) {
         let renderer = renderer.downcast_mut::<WebRenderer>().unwrap();
         let web_handle = native_handle.downcast_ref::<WebNativeHandle>().unwrap();
-        // TODO: compare with old text?
-        let text_idx = renderer.string_idx(self.text.as_ref());
-        super::bridge::set_text_content(&web_handle.node, text_idx);
+        // Parents often rebuild their text with a new gen but the same string,
+        // and writing it anyway would still invalidate layout
+        let mut text = web_handle.text.borrow_mut();
+        if *text != self.text {
+            renderer.set_text_content(web_handle.id.get(), &self.text);
+            text.clear();
+            text.push_str(&self.text);
+        }
     }
     
     fn native_children(self) -> &'a [View] {
@@ -103,7 +117,12 @@ impl<'a> Component<'a> for TextImpl<'a> {
 
 pub(crate) enum Attr<'a> {
     Prop(Option<Cow<'a, str>>),
-    Handler(Box<dyn Fn(WebNativeEvent) + 'a>),
+    Handler {
+        /// The name of the native event listened for.
+        event: &'static str,
+        options: ListenerOptions,
+        handler: Box<dyn Fn(WebNativeEvent) + 'a>,
+    },
 }
 
 trait IntoCowStr<'a> {
@@ -156,8 +175,18 @@ pub struct RawElement<'a> {
     pub(crate) children_gen: Gen<'a>,
     pub(crate) value_controlled: bool,
     pub(crate) checked_controlled: bool,
+    /// The values of the selected options of a controlled `Select`.
+    pub(crate) select_values: Option<Vec<Cow<'a, str>>>,
+    pub(crate) select_values_gen: Gen<'a>,
     pub(crate) location: (u32, u32),
     pub(crate) tag: &'static str,
+    /// The namespace of the element, or `None` for HTML elements.
+    pub(crate) namespace: Option<&'static str>,
+    pub(crate) node_ref: Option<RawNodeRef>,
+    pub(crate) styles: Option<Style<'a>>,
+    pub(crate) styles_gen: Gen<'a>,
+    pub(crate) classes: Option<ClassList<'a>>,
+    pub(crate) classes_gen: Gen<'a>,
 }
 
 impl<'a> RawElement<'a> {
@@ -169,8 +198,16 @@ impl<'a> RawElement<'a> {
             children_gen: Gen::escape_hatch_new(false),
             value_controlled: Default::default(),
             checked_controlled: Default::default(),
+            select_values: None,
+            select_values_gen: Gen::escape_hatch_new(false),
             location: Default::default(),
             tag: Default::default(),
+            namespace: None,
+            node_ref: None,
+            styles: None,
+            styles_gen: Gen::escape_hatch_new(false),
+            classes: None,
+            classes_gen: Gen::escape_hatch_new(false),
         }
     }
     fn set_attr(&mut self, name: &'static str, attr: Attr<'a>, gen: Gen<'a>) {
@@ -178,6 +215,37 @@ impl<'a> RawElement<'a> {
         self.max_gen = max(self.max_gen, gen);
     }
 
+    fn set_node_ref(&mut self, node_ref: RawNodeRef, gen: Gen<'a>) {
+        self.node_ref = Some(node_ref);
+        self.max_gen = max(self.max_gen, gen);
+    }
+
+    fn set_styles(&mut self, styles: Style<'a>, gen: Gen<'a>) {
+        self.styles = Some(styles);
+        self.styles_gen = gen;
+        self.max_gen = max(self.max_gen, gen);
+    }
+
+    /// Returns the element's style properties, with later values of the same property taking precedence.
+    fn style_properties(&self) -> FxHashMap<&str, &str> {
+        self.styles
+            .iter()
+            .flat_map(|styles| styles.properties.iter())
+            .map(|(name, value)| (name.as_ref(), value.as_ref()))
+            .collect()
+    }
+
+    fn set_classes(&mut self, classes: ClassList<'a>, gen: Gen<'a>) {
+        self.classes = Some(classes);
+        self.classes_gen = gen;
+        self.max_gen = max(self.max_gen, gen);
+    }
+
+    /// Returns the element's enabled classes.
+    fn enabled_classes(&self) -> FxHashSet<&str> {
+        self.classes.iter().flat_map(|classes| classes.enabled()).collect()
+    }
+
     fn set_children(&mut self, children: BumpVec<'a, View>, gen: Gen<'a>) {
         self.children = children;
         self.children_gen = gen;
@@ -191,55 +259,94 @@ impl<'a> Component<'a> for RawElement<'a> {
     
     fn native_create(&self, renderer: &mut dyn Renderer, dispatch_native_event: DispatchNativeEvent) -> NativeHandle {
         let renderer = renderer.downcast_mut::<WebRenderer>().unwrap();
-        let tag_idx = renderer.string_idx(self.tag);
-        let element: Element = 
-            super::bridge::create_element(tag_idx).unchecked_into();
+        let (id, element) = renderer.create_element(self.tag, self.namespace);
 
-        let mut listeners = FxHashMap::default();
+        let mut attributes = FxHashSet::default();
+        let mut listeners = Listeners::new(dispatch_native_event);
 
         if self.value_controlled {
-            add_named_listener(
-                &element,
-                "input",
-                "#v",
-                false,
-                |e| e.prevent_default(),
-                &mut listeners,
-            );
+            super::bridge::control_value(&element);
         }
         if self.checked_controlled {
-            add_named_listener(
+            add_listener(
                 &element,
                 "change",
                 "#c",
-                false,
+                ListenerOptions::default(),
                 |e| e.prevent_default(),
-                &mut listeners,
+                &mut listeners.native,
             );
         }
+        if let Some(values) = &self.select_values {
+            // Once any state updated by the change's handlers has been rerendered, restore the
+            // controlled selection in case the change was not accepted. Handlers, including delegated
+            // ones, may run after this listener and queue their rerenders after it, so the sync is
+            // queued again from the task running them, which makes it run in the task after.
+            let ui_queue = renderer.ui_queue.clone();
+            add_listener(
+                &element,
+                "change",
+                "#s",
+                ListenerOptions::default(),
+                move |e| {
+                    if let Some(select) = e.current_target() {
+                        let queue = ui_queue.clone();
+                        ui_queue.schedule(
+                            Box::new(move || {
+                                queue.schedule(
+                                    Box::new(move || super::bridge::sync_select(&select)),
+                                    Priority::Normal,
+                                )
+                            }),
+                            Priority::Normal,
+                        );
+                    }
+                },
+                &mut listeners.native,
+            );
+            renderer.set_select_values(id.get(), values.iter().map(AsRef::as_ref));
+        }
         
         for (name, (attr, _)) in self.attrs.iter() {
             match attr {
                 Attr::Prop(prop) => {
                     if let Some(prop) = prop {
-                        renderer.set_attribute(&element, name, prop);
+                        renderer.set_attribute(id.get(), name, prop);
+                        attributes.insert(*name);
                     }
                 }
-                Attr::Handler(_) => {
-                    let dispatcher = dispatch_native_event.clone();
-                    add_listener(
-                        &element,
-                        name,
-                        create_handler(name, dispatcher),
-                        &mut listeners,
-                    )
+                Attr::Handler { event, options, .. } => {
+                    listeners.attach(&renderer.delegator, &element, name, event, *options);
                 }
             }
         }
 
+        let mut styles = FxHashMap::default();
+        for (name, value) in self.style_properties() {
+            renderer.set_style_property(id.get(), name, value);
+            styles.insert(name.to_owned(), value.to_owned());
+        }
+
+        let mut classes = FxHashSet::default();
+        for name in self.enabled_classes() {
+            renderer.add_class(id.get(), name);
+            classes.insert(name.to_owned());
+        }
+
+        let node = web_sys::Node::from(element);
+        if let Some(node_ref) = &self.node_ref {
+            node_ref.replace(Some(node.clone()));
+        }
+
         Box::new(WebNativeHandle {
-            node: web_sys::Node::from(element),
-            _listeners: listeners,
+            id,
+            node,
+            attributes: RefCell::new(attributes),
+            listeners: RefCell::new(listeners),
+            node_ref: RefCell::new(self.node_ref.clone()),
+            styles: RefCell::new(styles),
+            classes: RefCell::new(classes),
+            text: Default::default(),
         })
     }
 
@@ -252,12 +359,12 @@ impl<'a> Component<'a> for RawElement<'a> {
     ) {
         let renderer = renderer.downcast_mut::<WebRenderer>().unwrap();
         let web_handle = native_handle.downcast_ref::<WebNativeHandle>().unwrap();
-        let node = web_handle.node.clone();
-        let element = node.unchecked_into::<web_sys::Element>();
+        let id = web_handle.id.get();
+        let element = web_handle.node.unchecked_ref::<web_sys::Element>();
 
         if let Some(native_event) = event {
             match &self.attrs[&native_event.name].0 {
-                Attr::Handler(handler) => {
+                Attr::Handler { handler, .. } => {
                     handler(
                         *native_event
                             .event
@@ -271,15 +378,111 @@ impl<'a> Component<'a> for RawElement<'a> {
             }
         }
 
+        // The element may have been given a different ref since the last render
+        let mut node_ref = web_handle.node_ref.borrow_mut();
+        let ref_changed = match (&*node_ref, &self.node_ref) {
+            (Some(old), Some(new)) => !Rc::ptr_eq(old, new),
+            (None, None) => false,
+            _ => true,
+        };
+        if ref_changed {
+            if let Some(old) = node_ref.take() {
+                old.replace(None);
+            }
+            if let Some(new) = &self.node_ref {
+                new.replace(Some(web_handle.node.clone()));
+            }
+            *node_ref = self.node_ref.clone();
+        }
+
+        let mut attributes = web_handle.attributes.borrow_mut();
         if self.max_gen >= curr_gen {
             for (name, (attr, gen)) in self.attrs.iter() {
                 if *gen >= curr_gen {
                     if let Attr::Prop(prop) = attr {
-                        renderer.set_attribute(&element, name, prop.as_deref().unwrap_or_default());
+                        let value = prop.as_deref().unwrap_or_default();
+                        renderer.set_attribute(id, name, value);
+                        if value.is_empty() {
+                            attributes.remove(name);
+                        } else {
+                            attributes.insert(*name);
+                        }
                     }
                 }
             }
         }
+
+        // Attributes set on a previous render but omitted from this one are removed
+        let stale: Vec<_> = attributes
+            .iter()
+            .filter(|name| !matches!(self.attrs.get(*name), Some((Attr::Prop(_), _))))
+            .copied()
+            .collect();
+        for name in stale {
+            renderer.set_attribute(id, name, "");
+            attributes.remove(name);
+        }
+
+        if self.select_values_gen >= curr_gen {
+            if let Some(values) = &self.select_values {
+                renderer.set_select_values(id, values.iter().map(AsRef::as_ref));
+            }
+        }
+
+        // Only the style properties that changed are set
+        let mut styles = web_handle.styles.borrow_mut();
+        if self.styles_gen >= curr_gen || (self.styles.is_none() && !styles.is_empty()) {
+            let properties = self.style_properties();
+            styles.retain(|name, _| {
+                let retained = properties.contains_key(name.as_str());
+                if !retained {
+                    renderer.remove_style_property(id, name);
+                }
+                retained
+            });
+            for (name, value) in properties {
+                if styles.get(name).map(String::as_str) != Some(value) {
+                    renderer.set_style_property(id, name, value);
+                    styles.insert(name.to_owned(), value.to_owned());
+                }
+            }
+        }
+
+        // Only the classes that were enabled or disabled are changed
+        let mut classes = web_handle.classes.borrow_mut();
+        if self.classes_gen >= curr_gen || (self.classes.is_none() && !classes.is_empty()) {
+            let enabled = self.enabled_classes();
+            classes.retain(|name| {
+                let retained = enabled.contains(name.as_str());
+                if !retained {
+                    renderer.remove_class(id, name);
+                }
+                retained
+            });
+            for name in enabled {
+                if !classes.contains(name) {
+                    renderer.add_class(id, name);
+                    classes.insert(name.to_owned());
+                }
+            }
+        }
+
+        // Likewise, handlers may have been added, removed, or given new options since the last render
+        let mut listeners = web_handle.listeners.borrow_mut();
+        let removed: Vec<_> = listeners
+            .attached
+            .keys()
+            .filter(|name| !matches!(self.attrs.get(*name), Some((Attr::Handler { .. }, _))))
+            .copied()
+            .collect();
+        for name in removed {
+            listeners.detach(&renderer.delegator, name);
+        }
+        for (name, (attr, _)) in self.attrs.iter() {
+            if let Attr::Handler { event, options, .. } = attr {
+                listeners.attach(&renderer.delegator, element, name, event, *options);
+            }
+        }
     }
     
     fn native_children(self) -> &'a [View] {
@@ -370,6 +573,23 @@ macro_rules! def_component {
         $native_element:path;
         $tag:ident;
         $tag_impl:ident;
+    ) => {
+        def_component! {
+            $native_tag;
+            $native_element;
+            $tag;
+            $tag_impl;
+            None;
+            add_global_attrs;
+        }
+    };
+    (
+        $native_tag:expr;
+        $native_element:path;
+        $tag:ident;
+        $tag_impl:ident;
+        $namespace:expr;
+        $global_attrs:ident;
     ) => {
         pub struct $tag_impl<'a>(PhantomData<&'a ()>);
 
@@ -403,6 +623,7 @@ macro_rules! def_component {
             pub fn build(mut self, location: (u32, u32)) -> RawElement<'a> {
                 self.raw.location = location;
                 self.raw.tag = $native_tag;
+                self.raw.namespace = $namespace;
                 self.raw
             }
 
@@ -415,13 +636,29 @@ macro_rules! def_component {
             pub fn __last<I: IntoIterator<Item=View>>(self, children: I, gen: Gen<'a>) -> Self {
                 self.children(children, gen)
             }
+
+            pub fn styles(mut self, styles: $crate::style::Style<'a>, gen: Gen<'a>) -> Self {
+                self.raw.set_styles(styles, gen);
+                self
+            }
+
+            pub fn classes(mut self, classes: impl Into<$crate::class_list::ClassList<'a>>, gen: Gen<'a>) -> Self {
+                self.raw.set_classes(classes.into(), gen);
+                self
+            }
+
+            pub fn node_ref(mut self, node_ref: $crate::NodeRef<$native_element>, gen: Gen<'a>) -> Self {
+                self.raw.set_node_ref(node_ref.node, gen);
+                self
+            }
         }
 
         impl<'a> AssociatedNativeElement for $tag<'a> {
             type NativeElement = $native_element;
         }
 
-        add_global_attrs! {$tag}
+        $global_attrs! {$tag}
+        add_global_listeners! {$tag}
     };
 }
 
@@ -431,7 +668,7 @@ macro_rules! def_component_attrs {
         $l:lifetime;
         props: $($propnative:expr => $propident:ident : $proptype:ty),*;
         $(bool_props: $($boolpropnative: expr => $boolpropident:ident),*;)?
-        $(listeners: $($listennative:expr => $listenident:ident : $listentype:ty),*;)?
+        $(listeners: $($listennative:literal => $listenident:ident, $listenwithident:ident : $listentype:ty),*;)?
     ) => {
         macro_rules! $mac {
             ($tag:ident) => {
@@ -465,9 +702,34 @@ macro_rules! def_component_attrs {
                             pub fn $listenident(mut self, f: impl Fn(TypedEvent::<$listentype, <$tag as AssociatedNativeElement>::NativeElement>) + 'a, gen: Gen<'a>) -> Self {
                                 self.raw.set_attr(
                                     $listennative,
-                                    Attr::Handler(Box::new(move |e: WebNativeEvent| f(
-                                        TypedEvent::<$listentype, <$tag as AssociatedNativeElement>::NativeElement>::new(e.event.unchecked_into::<$listentype>(), e.current_target)
-                                    ))),
+                                    Attr::Handler {
+                                        event: $listennative,
+                                        options: ListenerOptions::default(),
+                                        handler: Box::new(move |e: WebNativeEvent| f(
+                                            TypedEvent::<$listentype, <$tag as AssociatedNativeElement>::NativeElement>::new(e.event.unchecked_into::<$listentype>(), e.current_target)
+                                        )),
+                                    },
+                                    gen
+                                );
+                                self
+                            }
+
+                            pub fn $listenwithident(mut self, (options, f): (ListenerOptions, impl Fn(TypedEvent::<$listentype, <$tag as AssociatedNativeElement>::NativeElement>) + 'a), gen: Gen<'a>) -> Self {
+                                // capture listeners are distinct from bubble listeners, so they get their own key
+                                let name = if options.capture {
+                                    concat!($listennative, "#capture")
+                                } else {
+                                    $listennative
+                                };
+                                self.raw.set_attr(
+                                    name,
+                                    Attr::Handler {
+                                        event: $listennative,
+                                        options,
+                                        handler: Box::new(move |e: WebNativeEvent| f(
+                                            TypedEvent::<$listentype, <$tag as AssociatedNativeElement>::NativeElement>::new(e.event.unchecked_into::<$listentype>(), e.current_target)
+                                        )),
+                                    },
                                     gen
                                 );
                                 self
@@ -499,13 +761,33 @@ def_component_attrs! {
         "style" => style: Cow<'a, str>,
         "tabindex" => tab_index: i16,
         "title" => title: Cow<'a, str>,
-        "translate" => translate: Translate;
+        "translate" => translate: Translate,
+        "role" => role: Cow<'a, str>,
+        "aria-controls" => aria_controls: Cow<'a, str>,
+        "aria-current" => aria_current: Cow<'a, str>,
+        "aria-describedby" => aria_described_by: Cow<'a, str>,
+        "aria-disabled" => aria_disabled: Cow<'a, str>,
+        "aria-expanded" => aria_expanded: Cow<'a, str>,
+        "aria-haspopup" => aria_has_popup: Cow<'a, str>,
+        "aria-hidden" => aria_hidden: Cow<'a, str>,
+        "aria-label" => aria_label: Cow<'a, str>,
+        "aria-labelledby" => aria_labelled_by: Cow<'a, str>,
+        "aria-live" => aria_live: Cow<'a, str>,
+        "aria-pressed" => aria_pressed: Cow<'a, str>,
+        "aria-selected" => aria_selected: Cow<'a, str>;
     bool_props:
         "hidden" => hidden;
+}
+
+def_component_attrs! {
+    add_global_listeners;
+    'a;
+    props:
+        ;
     listeners:
         // Focus events
-        "blur" => on_blur: FocusEvent,
-        "focus" => on_focus: FocusEvent,
+        "blur" => on_blur, on_blur_with: FocusEvent,
+        "focus" => on_focus, on_focus_with: FocusEvent,
         //focusin, focusout?
 
         // Clipboard events
@@ -513,111 +795,111 @@ def_component_attrs! {
         // cut, copy, and paste
 
         // Composition events
-        "compositionstart" => on_composition_start: CompositionEvent,
-        "compositionupdate" => on_composition_update: CompositionEvent,
-        "compositionend" => on_composition_end: CompositionEvent,
+        "compositionstart" => on_composition_start, on_composition_start_with: CompositionEvent,
+        "compositionupdate" => on_composition_update, on_composition_update_with: CompositionEvent,
+        "compositionend" => on_composition_end, on_composition_end_with: CompositionEvent,
 
         // Form events
-        "change" => on_change: Event,
-        "input" => on_input: Event,
+        "change" => on_change, on_change_with: Event,
+        "input" => on_input, on_input_with: Event,
         // TODO: for form only?
-        "reset" => on_reset: Event,
-        "submit" => on_submit: Event,
-        "invalid" => on_invalid: Event,
+        "reset" => on_reset, on_reset_with: Event,
+        "submit" => on_submit, on_submit_with: Event,
+        "invalid" => on_invalid, on_invalid_with: Event,
 
         // Image events
-        "load" => on_load: Event,
-        "error" => on_error: Event,
+        "load" => on_load, on_load_with: Event,
+        "error" => on_error, on_error_with: Event,
 
         // Keyboard events
-        "keydown" => on_key_down: KeyboardEvent,
-        "keyup" => on_key_up: KeyboardEvent,
+        "keydown" => on_key_down, on_key_down_with: KeyboardEvent,
+        "keyup" => on_key_up, on_key_up_with: KeyboardEvent,
 
         // Media events
-        "canplay" => on_can_play: Event,
-        "canplaythrough" => on_can_play_through: Event,
-        "durationchange" => on_duration_change: Event,
-        "emptied" => on_emptied: Event,
-        "ended" => on_ended: Event,
-        "loadeddata" => on_loaded_data: Event,
-        "loadedmetadata" => on_loaded_metadata: Event,
-        "pause" => on_pause: Event,
-        "play" => on_play: Event,
-        "playing" => on_playing: Event,
-        "ratechange" => on_rate_change: Event,
-        "seeked" => on_seeked: Event,
-        "seeking" => on_seeking: Event,
-        "stalled" => on_stalled: Event,
-        "suspend" => on_suspend: Event,
-        "timeupdate" => on_time_update: Event,
-        "volumechange" => on_volume_change: Event,
-        "waiting" => on_waiting: Event,
+        "canplay" => on_can_play, on_can_play_with: Event,
+        "canplaythrough" => on_can_play_through, on_can_play_through_with: Event,
+        "durationchange" => on_duration_change, on_duration_change_with: Event,
+        "emptied" => on_emptied, on_emptied_with: Event,
+        "ended" => on_ended, on_ended_with: Event,
+        "loadeddata" => on_loaded_data, on_loaded_data_with: Event,
+        "loadedmetadata" => on_loaded_metadata, on_loaded_metadata_with: Event,
+        "pause" => on_pause, on_pause_with: Event,
+        "play" => on_play, on_play_with: Event,
+        "playing" => on_playing, on_playing_with: Event,
+        "ratechange" => on_rate_change, on_rate_change_with: Event,
+        "seeked" => on_seeked, on_seeked_with: Event,
+        "seeking" => on_seeking, on_seeking_with: Event,
+        "stalled" => on_stalled, on_stalled_with: Event,
+        "suspend" => on_suspend, on_suspend_with: Event,
+        "timeupdate" => on_time_update, on_time_update_with: Event,
+        "volumechange" => on_volume_change, on_volume_change_with: Event,
+        "waiting" => on_waiting, on_waiting_with: Event,
 
         // Mouse events
-        "auxclick" => on_aux_click: MouseEvent,
-        "click" => on_click: MouseEvent,
-        "contextmenu" => on_context_menu: MouseEvent,
-        "dblclick" => on_double_click: MouseEvent,
-        "mousedown" => on_mouse_down: MouseEvent,
-        "mouseenter" => on_mouse_enter: MouseEvent,
-        "mouseleave" => on_mouse_leave: MouseEvent,
-        "mousemove" => on_mouse_move: MouseEvent,
-        "mouseover" => on_mouse_over: MouseEvent,
-        "mouseout" => on_mouse_out: MouseEvent,
-        "mouseup" => on_mouse_up: MouseEvent,
-        "pointerlockchange" => on_pointer_lock_change: Event,
-        "pointerlockerror" => on_pointer_lock_error: Event,
-        "select" => on_select: Event,
+        "auxclick" => on_aux_click, on_aux_click_with: MouseEvent,
+        "click" => on_click, on_click_with: MouseEvent,
+        "contextmenu" => on_context_menu, on_context_menu_with: MouseEvent,
+        "dblclick" => on_double_click, on_double_click_with: MouseEvent,
+        "mousedown" => on_mouse_down, on_mouse_down_with: MouseEvent,
+        "mouseenter" => on_mouse_enter, on_mouse_enter_with: MouseEvent,
+        "mouseleave" => on_mouse_leave, on_mouse_leave_with: MouseEvent,
+        "mousemove" => on_mouse_move, on_mouse_move_with: MouseEvent,
+        "mouseover" => on_mouse_over, on_mouse_over_with: MouseEvent,
+        "mouseout" => on_mouse_out, on_mouse_out_with: MouseEvent,
+        "mouseup" => on_mouse_up, on_mouse_up_with: MouseEvent,
+        "pointerlockchange" => on_pointer_lock_change, on_pointer_lock_change_with: Event,
+        "pointerlockerror" => on_pointer_lock_error, on_pointer_lock_error_with: Event,
+        "select" => on_select, on_select_with: Event,
 
         // Wheel event
-        "wheel" => on_wheel: WheelEvent,
+        "wheel" => on_wheel, on_wheel_with: WheelEvent,
 
         // Drag and drop events
-        "drag" => on_drag: DragEvent,
-        "dragend" => on_drag_end: DragEvent,
-        "dragenter" => on_drag_enter: DragEvent,
-        "dragstart" => on_drag_start: DragEvent,
-        "dragleave" => on_drag_leave: DragEvent,
-        "dragover" => on_drag_over: DragEvent,
-        "drop" => on_drop: DragEvent,
+        "drag" => on_drag, on_drag_with: DragEvent,
+        "dragend" => on_drag_end, on_drag_end_with: DragEvent,
+        "dragenter" => on_drag_enter, on_drag_enter_with: DragEvent,
+        "dragstart" => on_drag_start, on_drag_start_with: DragEvent,
+        "dragleave" => on_drag_leave, on_drag_leave_with: DragEvent,
+        "dragover" => on_drag_over, on_drag_over_with: DragEvent,
+        "drop" => on_drop, on_drop_with: DragEvent,
 
         // Touch events
-        "touchcancel" => on_touch_cancel: TouchEvent,
-        "touchend" => on_touch_end: TouchEvent,
-        "touchmove" => on_touch_move: TouchEvent,
-        "touchstart" => on_touch_start: TouchEvent,
+        "touchcancel" => on_touch_cancel, on_touch_cancel_with: TouchEvent,
+        "touchend" => on_touch_end, on_touch_end_with: TouchEvent,
+        "touchmove" => on_touch_move, on_touch_move_with: TouchEvent,
+        "touchstart" => on_touch_start, on_touch_start_with: TouchEvent,
 
         // Pointer events
-        "pointerover" => on_pointer_over: PointerEvent,
-        "pointerenter" => on_pointer_enter: PointerEvent,
-        "pointerdown" => on_pointer_down: PointerEvent,
-        "pointermove" => on_pointer_move: PointerEvent,
-        "pointerup" => on_pointer_up: PointerEvent,
-        "pointercancel" => on_pointer_cancel: PointerEvent,
-        "pointerout" => on_pointer_out: PointerEvent,
-        "pointerleave" => on_pointer_leave: PointerEvent,
-        "gotpointercapture" => on_got_pointer_capture: PointerEvent,
-        "lostpointercapture" => on_lost_pointer_capture: PointerEvent,
+        "pointerover" => on_pointer_over, on_pointer_over_with: PointerEvent,
+        "pointerenter" => on_pointer_enter, on_pointer_enter_with: PointerEvent,
+        "pointerdown" => on_pointer_down, on_pointer_down_with: PointerEvent,
+        "pointermove" => on_pointer_move, on_pointer_move_with: PointerEvent,
+        "pointerup" => on_pointer_up, on_pointer_up_with: PointerEvent,
+        "pointercancel" => on_pointer_cancel, on_pointer_cancel_with: PointerEvent,
+        "pointerout" => on_pointer_out, on_pointer_out_with: PointerEvent,
+        "pointerleave" => on_pointer_leave, on_pointer_leave_with: PointerEvent,
+        "gotpointercapture" => on_got_pointer_capture, on_got_pointer_capture_with: PointerEvent,
+        "lostpointercapture" => on_lost_pointer_capture, on_lost_pointer_capture_with: PointerEvent,
 
         // Scroll event
-        "scroll" => on_scroll: Event,
+        "scroll" => on_scroll, on_scroll_with: Event,
 
         // Animation events
-        "animationstart" => on_animation_start: AnimationEvent,
-        "animationcancel" => on_animation_cancel: AnimationEvent,
-        "animationend" => on_animation_end: AnimationEvent,
-        "animationinteraction" => on_animation_interaction: AnimationEvent,
+        "animationstart" => on_animation_start, on_animation_start_with: AnimationEvent,
+        "animationcancel" => on_animation_cancel, on_animation_cancel_with: AnimationEvent,
+        "animationend" => on_animation_end, on_animation_end_with: AnimationEvent,
+        "animationinteraction" => on_animation_interaction, on_animation_interaction_with: AnimationEvent,
 
         // Transition events
-        "transitionstart" => on_transition_start: TransitionEvent,
-        "transitioncancel" => on_transition_cancel: TransitionEvent,
-        "transitionend" => on_transition_end: TransitionEvent,
-        "transitionrun" => on_transition_run: TransitionEvent,
+        "transitionstart" => on_transition_start, on_transition_start_with: TransitionEvent,
+        "transitioncancel" => on_transition_cancel, on_transition_cancel_with: TransitionEvent,
+        "transitionend" => on_transition_end, on_transition_end_with: TransitionEvent,
+        "transitionrun" => on_transition_run, on_transition_run_with: TransitionEvent,
 
         // Progress events
-        "abort" => on_abort: Event,
-        "loadstart" => on_load_start: ProgressEvent,
-        "progress" => on_progress: ProgressEvent;
+        "abort" => on_abort, on_abort_with: Event,
+        "loadstart" => on_load_start, on_load_start_with: ProgressEvent,
+        "progress" => on_progress, on_progress_with: ProgressEvent;
 }
 
 def_component_attrs! {
@@ -1931,7 +2213,6 @@ def_component_attrs! {
 
 add_progress_attrs! {Progress}
 
-// TODO: add controlled functionality
 def_component! {
     "select";
     web_sys::HtmlSelectElement;
@@ -1953,6 +2234,29 @@ def_component_attrs! {
 }
 add_select_attrs! {Select}
 
+impl<'a> Select<'a> {
+    /// Selects the option with the given value, keeping the selection in sync with `val`
+    /// when the user changes it. Use `values` instead for `multiple` selects.
+    pub fn value<S: Into<Cow<'a, str>>>(mut self, val: S, gen: Gen<'a>) -> Self {
+        self.raw.select_values = Some(vec![val.into()]);
+        self.raw.select_values_gen = gen;
+        self.raw.max_gen = max(self.raw.max_gen, gen);
+        self
+    }
+
+    /// Like `value`, but selects every option whose value is in `vals`.
+    pub fn values<I, S>(mut self, vals: I, gen: Gen<'a>) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<Cow<'a, str>>,
+    {
+        self.raw.select_values = Some(vals.into_iter().map(Into::into).collect());
+        self.raw.select_values_gen = gen;
+        self.raw.max_gen = max(self.raw.max_gen, gen);
+        self
+    }
+}
+
 def_component_attrs! {
     add_open_attr;
     'a;
@@ -1984,3 +2288,5 @@ def_component! {
     Summary;
     SummaryImpl;
 }
+
+pub mod svg;
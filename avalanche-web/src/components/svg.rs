@@ -0,0 +1,667 @@
+//! SVG elements, created within the SVG namespace.
+//!
+//! Element names that clash with HTML elements, like [Text] and [A], are only accessible through this module,
+//! so it is usually imported as a whole:
+//!
+//! ```rust
+//! use avalanche::{component, View};
+//! use avalanche_web::components::{svg, Text};
+//!
+//! #[component]
+//! fn Logo() -> View {
+//!     svg::Svg(
+//!         self,
+//!         view_box = "0 0 100 100",
+//!         width = "100",
+//!         height = "100",
+//!         [
+//!             svg::Circle(self, cx = 50.0, cy = 50.0, r = 40.0, fill = "tomato"),
+//!             svg::Text(
+//!                 self,
+//!                 x = 50.0,
+//!                 y = 55.0,
+//!                 text_anchor = svg::TextAnchor::Middle,
+//!                 Text(self, "logo")
+//!             )
+//!         ]
+//!     )
+//! }
+//! ```
+//!
+//! Namespaced attributes, like `xlink:href`, are set with `setAttributeNS`.
+
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::marker::PhantomData;
+
+use super::{AssociatedNativeElement, Attr, IntoCowStr, RawElement};
+use crate::{events::*, WebNativeEvent};
+use avalanche::alloc::{Bump, CollectIn};
+use avalanche::hooks::{HookContext, RenderContext};
+use avalanche::tracked::Gen;
+use avalanche::View;
+
+use wasm_bindgen::JsCast;
+
+/// The namespace of SVG elements.
+pub const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+/// The namespace of `xlink:` attributes.
+pub const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+/// The namespace of `xml:` attributes.
+pub const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+#[derive(Debug, Copy, Clone)]
+pub enum FillRule {
+    NonZero,
+    EvenOdd,
+}
+
+impl FillRule {
+    fn as_str(self) -> &'static str {
+        match self {
+            FillRule::NonZero => "nonzero",
+            FillRule::EvenOdd => "evenodd",
+        }
+    }
+}
+
+impl Display for FillRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl<'a> IntoCowStr<'a> for FillRule {
+    fn into_cow_str(self) -> Cow<'a, str> {
+        Cow::Borrowed(self.as_str())
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum LineCap {
+    Butt,
+    Round,
+    Square,
+}
+
+impl LineCap {
+    fn as_str(self) -> &'static str {
+        match self {
+            LineCap::Butt => "butt",
+            LineCap::Round => "round",
+            LineCap::Square => "square",
+        }
+    }
+}
+
+impl Display for LineCap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl<'a> IntoCowStr<'a> for LineCap {
+    fn into_cow_str(self) -> Cow<'a, str> {
+        Cow::Borrowed(self.as_str())
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum LineJoin {
+    Miter,
+    Round,
+    Bevel,
+}
+
+impl LineJoin {
+    fn as_str(self) -> &'static str {
+        match self {
+            LineJoin::Miter => "miter",
+            LineJoin::Round => "round",
+            LineJoin::Bevel => "bevel",
+        }
+    }
+}
+
+impl Display for LineJoin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl<'a> IntoCowStr<'a> for LineJoin {
+    fn into_cow_str(self) -> Cow<'a, str> {
+        Cow::Borrowed(self.as_str())
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum TextAnchor {
+    Start,
+    Middle,
+    End,
+}
+
+impl TextAnchor {
+    fn as_str(self) -> &'static str {
+        match self {
+            TextAnchor::Start => "start",
+            TextAnchor::Middle => "middle",
+            TextAnchor::End => "end",
+        }
+    }
+}
+
+impl Display for TextAnchor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl<'a> IntoCowStr<'a> for TextAnchor {
+    fn into_cow_str(self) -> Cow<'a, str> {
+        Cow::Borrowed(self.as_str())
+    }
+}
+
+/// The coordinate system of attributes like `gradientUnits` and `clipPathUnits`.
+#[derive(Debug, Copy, Clone)]
+pub enum Units {
+    UserSpaceOnUse,
+    ObjectBoundingBox,
+}
+
+impl Units {
+    fn as_str(self) -> &'static str {
+        match self {
+            Units::UserSpaceOnUse => "userSpaceOnUse",
+            Units::ObjectBoundingBox => "objectBoundingBox",
+        }
+    }
+}
+
+impl Display for Units {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl<'a> IntoCowStr<'a> for Units {
+    fn into_cow_str(self) -> Cow<'a, str> {
+        Cow::Borrowed(self.as_str())
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum SpreadMethod {
+    Pad,
+    Reflect,
+    Repeat,
+}
+
+impl SpreadMethod {
+    fn as_str(self) -> &'static str {
+        match self {
+            SpreadMethod::Pad => "pad",
+            SpreadMethod::Reflect => "reflect",
+            SpreadMethod::Repeat => "repeat",
+        }
+    }
+}
+
+impl Display for SpreadMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl<'a> IntoCowStr<'a> for SpreadMethod {
+    fn into_cow_str(self) -> Cow<'a, str> {
+        Cow::Borrowed(self.as_str())
+    }
+}
+
+def_component_attrs! {
+    add_svg_global_attrs;
+    'a;
+    props:
+        "class" => class: Cow<'a, str>,
+        "id" => id: Cow<'a, str>,
+        "lang" => lang: Cow<'a, str>,
+        "style" => style: Cow<'a, str>,
+        "tabindex" => tab_index: i16,
+        "xml:lang" => xml_lang: Cow<'a, str>,
+
+        // Presentation attributes
+        "clip-path" => clip_path: Cow<'a, str>,
+        "clip-rule" => clip_rule: FillRule,
+        "color" => color: Cow<'a, str>,
+        "display" => display: Cow<'a, str>,
+        "fill" => fill: Cow<'a, str>,
+        "fill-opacity" => fill_opacity: f64,
+        "fill-rule" => fill_rule: FillRule,
+        "filter" => filter: Cow<'a, str>,
+        "font-family" => font_family: Cow<'a, str>,
+        "font-size" => font_size: f64,
+        "font-weight" => font_weight: Cow<'a, str>,
+        "mask" => mask: Cow<'a, str>,
+        "opacity" => opacity: f64,
+        "stroke" => stroke: Cow<'a, str>,
+        "stroke-dasharray" => stroke_dash_array: Cow<'a, str>,
+        "stroke-dashoffset" => stroke_dash_offset: f64,
+        "stroke-linecap" => stroke_line_cap: LineCap,
+        "stroke-linejoin" => stroke_line_join: LineJoin,
+        "stroke-opacity" => stroke_opacity: f64,
+        "stroke-width" => stroke_width: f64,
+        "text-anchor" => text_anchor: TextAnchor,
+        "transform" => transform: Cow<'a, str>,
+        "visibility" => visibility: Cow<'a, str>;
+}
+
+def_component_attrs! {
+    add_view_box_attrs;
+    'a;
+    props:
+        "viewBox" => view_box: Cow<'a, str>,
+        "preserveAspectRatio" => preserve_aspect_ratio: Cow<'a, str>;
+}
+
+def_component_attrs! {
+    add_xy_attrs;
+    'a;
+    props:
+        "x" => x: f64,
+        "y" => y: f64;
+}
+
+def_component_attrs! {
+    add_svg_width_height_attrs;
+    'a;
+    props:
+        "width" => width: f64,
+        "height" => height: f64;
+}
+
+def_component_attrs! {
+    add_href_attrs;
+    'a;
+    props:
+        "href" => href: Cow<'a, str>,
+        "xlink:href" => xlink_href: Cow<'a, str>;
+}
+
+def_component! {
+    "svg";
+    web_sys::SvgsvgElement;
+    Svg;
+    SvgImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+add_view_box_attrs! {Svg}
+add_xy_attrs! {Svg}
+
+def_component_attrs! {
+    add_svg_attrs;
+    'a;
+    props:
+        // May be given as percentages
+        "width" => width: Cow<'a, str>,
+        "height" => height: Cow<'a, str>;
+}
+add_svg_attrs! {Svg}
+
+def_component! {
+    "g";
+    web_sys::SvggElement;
+    G;
+    GImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+
+def_component! {
+    "defs";
+    web_sys::SvgDefsElement;
+    Defs;
+    DefsImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+
+def_component! {
+    "symbol";
+    web_sys::SvgSymbolElement;
+    Symbol;
+    SymbolImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+add_view_box_attrs! {Symbol}
+
+def_component! {
+    "use";
+    web_sys::SvgUseElement;
+    Use;
+    UseImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+add_href_attrs! {Use}
+add_xy_attrs! {Use}
+add_svg_width_height_attrs! {Use}
+
+def_component! {
+    "a";
+    web_sys::SvgaElement;
+    A;
+    AImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+add_href_attrs! {A}
+
+def_component_attrs! {
+    add_svg_a_attrs;
+    'a;
+    props:
+        "target" => target: Cow<'a, str>;
+}
+add_svg_a_attrs! {A}
+
+def_component! {
+    "title";
+    web_sys::SvgTitleElement;
+    Title;
+    TitleImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+
+def_component_attrs! {
+    add_path_length_attr;
+    'a;
+    props:
+        "pathLength" => path_length: f64;
+}
+
+def_component! {
+    "path";
+    web_sys::SvgPathElement;
+    Path;
+    PathImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+add_path_length_attr! {Path}
+
+def_component_attrs! {
+    add_path_attrs;
+    'a;
+    props:
+        "d" => d: Cow<'a, str>;
+}
+add_path_attrs! {Path}
+
+def_component_attrs! {
+    add_center_attrs;
+    'a;
+    props:
+        "cx" => cx: f64,
+        "cy" => cy: f64;
+}
+
+def_component! {
+    "circle";
+    web_sys::SvgCircleElement;
+    Circle;
+    CircleImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+add_center_attrs! {Circle}
+add_path_length_attr! {Circle}
+
+def_component_attrs! {
+    add_circle_attrs;
+    'a;
+    props:
+        "r" => r: f64;
+}
+add_circle_attrs! {Circle}
+
+def_component_attrs! {
+    add_radius_attrs;
+    'a;
+    props:
+        "rx" => rx: f64,
+        "ry" => ry: f64;
+}
+
+def_component! {
+    "ellipse";
+    web_sys::SvgEllipseElement;
+    Ellipse;
+    EllipseImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+add_center_attrs! {Ellipse}
+add_radius_attrs! {Ellipse}
+add_path_length_attr! {Ellipse}
+
+def_component! {
+    "rect";
+    web_sys::SvgRectElement;
+    Rect;
+    RectImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+add_xy_attrs! {Rect}
+add_svg_width_height_attrs! {Rect}
+add_radius_attrs! {Rect}
+add_path_length_attr! {Rect}
+
+def_component_attrs! {
+    add_line_attrs;
+    'a;
+    props:
+        "x1" => x1: f64,
+        "y1" => y1: f64,
+        "x2" => x2: f64,
+        "y2" => y2: f64;
+}
+
+def_component! {
+    "line";
+    web_sys::SvgLineElement;
+    Line;
+    LineImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+add_line_attrs! {Line}
+add_path_length_attr! {Line}
+
+def_component_attrs! {
+    add_points_attr;
+    'a;
+    props:
+        "points" => points: Cow<'a, str>;
+}
+
+def_component! {
+    "polyline";
+    web_sys::SvgPolylineElement;
+    Polyline;
+    PolylineImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+add_points_attr! {Polyline}
+add_path_length_attr! {Polyline}
+
+def_component! {
+    "polygon";
+    web_sys::SvgPolygonElement;
+    Polygon;
+    PolygonImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+add_points_attr! {Polygon}
+add_path_length_attr! {Polygon}
+
+def_component_attrs! {
+    add_text_attrs;
+    'a;
+    props:
+        "dx" => dx: f64,
+        "dy" => dy: f64,
+        "rotate" => rotate: Cow<'a, str>,
+        "textLength" => text_length: f64,
+        "dominant-baseline" => dominant_baseline: Cow<'a, str>;
+}
+
+def_component! {
+    "text";
+    web_sys::SvgTextElement;
+    Text;
+    TextImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+add_xy_attrs! {Text}
+add_text_attrs! {Text}
+
+def_component! {
+    "tspan";
+    web_sys::SvgtSpanElement;
+    TSpan;
+    TSpanImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+add_xy_attrs! {TSpan}
+add_text_attrs! {TSpan}
+
+def_component! {
+    "image";
+    web_sys::SvgImageElement;
+    Image;
+    ImageImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+add_href_attrs! {Image}
+add_xy_attrs! {Image}
+add_svg_width_height_attrs! {Image}
+
+def_component_attrs! {
+    add_image_attrs;
+    'a;
+    props:
+        "preserveAspectRatio" => preserve_aspect_ratio: Cow<'a, str>;
+}
+add_image_attrs! {Image}
+
+def_component_attrs! {
+    add_gradient_attrs;
+    'a;
+    props:
+        "gradientUnits" => gradient_units: Units,
+        "gradientTransform" => gradient_transform: Cow<'a, str>,
+        "spreadMethod" => spread_method: SpreadMethod;
+}
+
+def_component! {
+    "linearGradient";
+    web_sys::SvgLinearGradientElement;
+    LinearGradient;
+    LinearGradientImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+add_href_attrs! {LinearGradient}
+add_gradient_attrs! {LinearGradient}
+add_line_attrs! {LinearGradient}
+
+def_component! {
+    "radialGradient";
+    web_sys::SvgRadialGradientElement;
+    RadialGradient;
+    RadialGradientImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+add_href_attrs! {RadialGradient}
+add_gradient_attrs! {RadialGradient}
+add_center_attrs! {RadialGradient}
+add_circle_attrs! {RadialGradient}
+
+def_component_attrs! {
+    add_radial_gradient_attrs;
+    'a;
+    props:
+        "fx" => fx: f64,
+        "fy" => fy: f64,
+        "fr" => fr: f64;
+}
+add_radial_gradient_attrs! {RadialGradient}
+
+def_component! {
+    "stop";
+    web_sys::SvgStopElement;
+    Stop;
+    StopImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+
+def_component_attrs! {
+    add_stop_attrs;
+    'a;
+    props:
+        "offset" => offset: f64,
+        "stop-color" => stop_color: Cow<'a, str>,
+        "stop-opacity" => stop_opacity: f64;
+}
+add_stop_attrs! {Stop}
+
+def_component! {
+    "clipPath";
+    web_sys::SvgClipPathElement;
+    ClipPath;
+    ClipPathImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+
+def_component_attrs! {
+    add_clip_path_attrs;
+    'a;
+    props:
+        "clipPathUnits" => clip_path_units: Units;
+}
+add_clip_path_attrs! {ClipPath}
+
+def_component! {
+    "mask";
+    web_sys::SvgMaskElement;
+    Mask;
+    MaskImpl;
+    Some(SVG_NAMESPACE);
+    add_svg_global_attrs;
+}
+add_xy_attrs! {Mask}
+add_svg_width_height_attrs! {Mask}
+
+def_component_attrs! {
+    add_mask_attrs;
+    'a;
+    props:
+        "maskUnits" => mask_units: Units,
+        "maskContentUnits" => mask_content_units: Units;
+}
+add_mask_attrs! {Mask}
@@ -0,0 +1,234 @@
+//! Event delegation: instead of attaching a listener to every element with a handler,
+//! a single listener per event type and phase is attached to the mount element.
+//! Events reaching it are routed to the handlers of the elements they passed through.
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use avalanche::renderer::{DispatchNativeEvent, NativeEvent};
+use gloo_events::{EventListener, EventListenerOptions, EventListenerPhase};
+use rustc_hash::FxHashMap;
+use wasm_bindgen::{intern, JsCast};
+use web_sys::{Element, Node};
+
+use crate::events::{Event, ListenerOptions};
+use crate::{bridge, WebNativeEvent};
+
+/// Returns whether handlers for `event` with the given `options` are delegated to the mount element.
+///
+/// Only events that bubble are delegated. Scroll-blocking events are excluded as well, since a
+/// non-passive listener on the mount element would delay scrolling anywhere within it.
+pub(crate) fn is_delegated(event: &str, options: ListenerOptions) -> bool {
+    !options.passive
+        && !options.once
+        && matches!(
+            event,
+            "auxclick"
+                | "click"
+                | "contextmenu"
+                | "dblclick"
+                | "mousedown"
+                | "mousemove"
+                | "mouseover"
+                | "mouseout"
+                | "mouseup"
+                | "keydown"
+                | "keyup"
+                | "input"
+                | "change"
+                | "submit"
+                | "reset"
+                | "select"
+                | "compositionstart"
+                | "compositionupdate"
+                | "compositionend"
+                | "drag"
+                | "dragend"
+                | "dragenter"
+                | "dragstart"
+                | "dragleave"
+                | "dragover"
+                | "drop"
+                | "touchend"
+                | "touchcancel"
+                | "pointerover"
+                | "pointerdown"
+                | "pointermove"
+                | "pointerup"
+                | "pointercancel"
+                | "pointerout"
+                | "gotpointercapture"
+                | "lostpointercapture"
+                | "animationstart"
+                | "animationcancel"
+                | "animationend"
+                | "animationiteration"
+                | "transitionstart"
+                | "transitioncancel"
+                | "transitionend"
+                | "transitionrun"
+        )
+}
+
+/// A handler registered with the [`Delegator`].
+pub(crate) struct DelegatedHandler {
+    /// The name of the native event listened for.
+    pub(crate) event: &'static str,
+    /// Whether the handler runs in the capture phase.
+    pub(crate) capture: bool,
+    /// The name the handler is dispatched with.
+    pub(crate) name: &'static str,
+}
+
+/// An element with delegated handlers.
+struct DelegateTarget {
+    node: Node,
+    dispatcher: DispatchNativeEvent,
+    /// Maps event names and phases to handler names.
+    handlers: FxHashMap<(&'static str, bool), &'static str>,
+}
+
+struct DelegatorState {
+    root: Element,
+    root_listeners: FxHashMap<(&'static str, bool), EventListener>,
+    targets: FxHashMap<u32, DelegateTarget>,
+    /// The id of the next registered target. `0` is reserved for nodes without handlers.
+    next_id: u32,
+}
+
+/// Routes events from listeners on the mount element to the components whose elements they passed through.
+#[derive(Clone)]
+pub(crate) struct Delegator {
+    state: Rc<RefCell<DelegatorState>>,
+}
+
+impl Delegator {
+    pub(crate) fn new(root: Element) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(DelegatorState {
+                root,
+                root_listeners: FxHashMap::default(),
+                targets: FxHashMap::default(),
+                next_id: 1,
+            })),
+        }
+    }
+
+    /// Registers the element `node`, whose handlers are dispatched through `dispatcher`.
+    /// The element and its handlers are unregistered when the returned guard is dropped.
+    pub(crate) fn register(&self, node: &Node, dispatcher: DispatchNativeEvent) -> DelegateGuard {
+        let mut state = self.state.borrow_mut();
+        let id = state.next_id;
+        state.next_id += 1;
+
+        bridge::set_delegate_id(node, id);
+        state.targets.insert(
+            id,
+            DelegateTarget {
+                node: node.clone(),
+                dispatcher,
+                handlers: FxHashMap::default(),
+            },
+        );
+
+        DelegateGuard {
+            state: Rc::downgrade(&self.state),
+            id,
+        }
+    }
+
+    /// Adds `handler` to the element registered with `guard`.
+    pub(crate) fn add_handler(&self, guard: &DelegateGuard, handler: DelegatedHandler) {
+        let mut state = self.state.borrow_mut();
+        let key = (handler.event, handler.capture);
+        if !state.root_listeners.contains_key(&key) {
+            let listener = self.root_listener(&state.root, handler.event, handler.capture);
+            state.root_listeners.insert(key, listener);
+        }
+        if let Some(target) = state.targets.get_mut(&guard.id) {
+            target.handlers.insert(key, handler.name);
+        }
+    }
+
+    /// Removes the handler for `event` in the given phase from the element registered with `guard`.
+    pub(crate) fn remove_handler(&self, guard: &DelegateGuard, event: &'static str, capture: bool) {
+        if let Some(target) = self.state.borrow_mut().targets.get_mut(&guard.id) {
+            target.handlers.remove(&(event, capture));
+        }
+    }
+
+    fn root_listener(&self, root: &Element, event: &'static str, capture: bool) -> EventListener {
+        let options = EventListenerOptions {
+            phase: if capture {
+                EventListenerPhase::Capture
+            } else {
+                EventListenerPhase::Bubble
+            },
+            passive: false,
+        };
+        intern(event);
+        // a weak reference keeps the listener from owning the state that owns it
+        let state = Rc::downgrade(&self.state);
+        EventListener::new_with_options(root, event, options, move |e| {
+            if let Some(state) = state.upgrade() {
+                dispatch(&state, event, capture, e);
+            }
+        })
+    }
+}
+
+/// Dispatches `event` to the handlers of the elements it passed through, in the order
+/// the browser would have run them had they been attached directly.
+fn dispatch(state: &RefCell<DelegatorState>, name: &'static str, capture: bool, event: &Event) {
+    let target = match event.target() {
+        Some(target) => target,
+        None => return,
+    };
+
+    // Handlers may add or remove elements, so the targets are collected before dispatching any,
+    // just as the browser fixes an event's path before dispatching it.
+    let mut targets: Vec<(Node, &'static str, DispatchNativeEvent)> = {
+        let state = state.borrow();
+        let path = bridge::delegate_path(&target, &state.root);
+        path.iter()
+            .filter_map(|id| state.targets.get(id))
+            .filter_map(|target| {
+                target
+                    .handlers
+                    .get(&(name, capture))
+                    .map(|handler| (target.node.clone(), *handler, target.dispatcher.clone()))
+            })
+            .collect()
+    };
+    // the path runs from the target upwards, but capturing runs from the root downwards
+    if capture {
+        targets.reverse();
+    }
+
+    for (node, handler, dispatcher) in targets {
+        dispatcher.dispatch(NativeEvent {
+            name: handler,
+            event: Box::new(WebNativeEvent {
+                event: event.clone(),
+                current_target: Some(node.unchecked_into()),
+            }),
+        });
+        if event.cancel_bubble() {
+            break;
+        }
+    }
+}
+
+/// Keeps an element's delegated handlers registered while alive.
+pub(crate) struct DelegateGuard {
+    state: Weak<RefCell<DelegatorState>>,
+    id: u32,
+}
+
+impl Drop for DelegateGuard {
+    fn drop(&mut self) {
+        if let Some(state) = self.state.upgrade() {
+            state.borrow_mut().targets.remove(&self.id);
+        }
+    }
+}
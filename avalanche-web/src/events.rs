@@ -40,6 +40,15 @@ impl<E: JsCast + Clone + Into<Event>, C: JsCast + Clone> TypedEvent<E, C> {
     }
 }
 
+impl<E: JsCast + Clone + Into<Event>, C: JsCast + Clone> Clone for TypedEvent<E, C> {
+    fn clone(&self) -> Self {
+        Self {
+            event: self.event.clone(),
+            current_target: self.current_target.clone(),
+        }
+    }
+}
+
 impl<E: JsCast + Clone + Into<Event>, C: JsCast> Deref for TypedEvent<E, C> {
     type Target = E;
 
@@ -47,3 +56,43 @@ impl<E: JsCast + Clone + Into<Event>, C: JsCast> Deref for TypedEvent<E, C> {
         &self.event
     }
 }
+
+/// Options controlling how an event listener is attached to its element.
+///
+/// Every `on_*` listener prop has an `on_*_with` counterpart accepting a tuple of
+/// options and handler, like `on_wheel_with = (ListenerOptions::PASSIVE, |e| ...)`.
+/// Capture listeners are attached separately from bubble listeners, so an element
+/// may have both an `on_click` and a capturing `on_click_with` handler.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ListenerOptions {
+    /// Whether the listener runs during the capture phase instead of the bubble phase.
+    pub capture: bool,
+    /// Whether the listener promises never to call `prevent_default`, allowing the
+    /// browser to scroll without waiting for it to run.
+    pub passive: bool,
+    /// Whether the listener runs at most once.
+    pub once: bool,
+}
+
+impl ListenerOptions {
+    /// Options for a listener that runs during the capture phase.
+    pub const CAPTURE: Self = Self {
+        capture: true,
+        passive: false,
+        once: false,
+    };
+
+    /// Options for a passive listener, which cannot prevent the event's default action.
+    pub const PASSIVE: Self = Self {
+        capture: false,
+        passive: true,
+        once: false,
+    };
+
+    /// Options for a listener that runs at most once.
+    pub const ONCE: Self = Self {
+        capture: false,
+        passive: false,
+        once: true,
+    };
+}
@@ -0,0 +1,132 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use avalanche::hooks::HookContext;
+use avalanche::{state, tracked};
+use gloo_events::EventListener;
+use wasm_bindgen::JsCast;
+use web_sys::{Event, EventTarget};
+
+type Handler = Rc<RefCell<Option<Box<dyn Fn(Event)>>>>;
+
+#[derive(Default)]
+struct GlobalListener {
+    event: Cell<Option<&'static str>>,
+    /// The handler passed on the latest render, called by `listener`.
+    handler: Handler,
+    listener: RefCell<Option<EventListener>>,
+}
+
+#[track_caller]
+fn global_event<E: JsCast>(
+    ctx: HookContext,
+    target: &EventTarget,
+    event: &'static str,
+    handler: impl Fn(E) + 'static,
+) {
+    let dispatcher = ctx.dispatcher();
+    let (global_listener, _) = state(ctx, GlobalListener::default);
+    let global_listener = tracked!(global_listener);
+    *global_listener.handler.borrow_mut() = Some(Box::new(move |event: Event| {
+        handler(event.unchecked_into())
+    }));
+
+    if global_listener.event.get() != Some(event) {
+        let handler = global_listener.handler.clone();
+        let listener = EventListener::new(target, event, move |event| {
+            let event = event.clone();
+            let handler = handler.clone();
+            // like element events, events fired while the tree renders are handled after the render
+            dispatcher.dispatch(move || {
+                if let Some(handler) = &*handler.borrow() {
+                    handler(event);
+                }
+            });
+        });
+        *global_listener.listener.borrow_mut() = Some(listener);
+        global_listener.event.set(Some(event));
+    }
+}
+
+/// A hook listening for `event` on the window, like `resize` or `popstate`, while the calling
+/// component is mounted.
+///
+/// `handler` is called with the event cast to `E`, such as `web_sys::KeyboardEvent`
+/// for `keydown`. Each render replaces the handler, so it may use the latest props and state.
+/// As with element listeners, the handler is called while the event is dispatched, unless the
+/// component tree is rendering, in which case it is called once the render completes, and state
+/// updates made by the handler are scheduled to rerender their components.
+/// Like [state](avalanche::state), `window_event` must be called with a context from [HookContext::with_key](avalanche::hooks::HookContext::with_key)
+/// within a loop or an iterator closure.
+///
+/// # Example
+/// ```rust
+/// use avalanche::{component, state, tracked, View};
+/// use avalanche_web::components::Text;
+/// use avalanche_web::window_event;
+/// use web_sys::Event;
+///
+/// #[component]
+/// fn WindowWidth() -> View {
+///     let (width, set_width) = state(self, || 0.0);
+///     let set_width = set_width.clone();
+///     window_event(self, "resize", move |_: Event| {
+///         let width = web_sys::window().unwrap().inner_width().unwrap();
+///         set_width.set(width.as_f64().unwrap());
+///     });
+///     Text(self, format!("{}px", tracked!(width)))
+/// }
+/// ```
+#[track_caller]
+pub fn window_event<E: JsCast>(
+    ctx: HookContext,
+    event: &'static str,
+    handler: impl Fn(E) + 'static,
+) {
+    let window = web_sys::window().unwrap();
+    global_event(ctx, &window, event, handler);
+}
+
+/// A hook listening for `event` on the document, like `keydown` or `click`, while the calling
+/// component is mounted. Handlers are called as in [window_event].
+///
+/// # Example
+/// Closing a menu on a click outside of it:
+/// ```rust
+/// use avalanche::{component, state, tracked, View};
+/// use avalanche_web::components::{Div, Text};
+/// use avalanche_web::{document_event, node_ref};
+/// use web_sys::{HtmlDivElement, MouseEvent, Node};
+/// use wasm_bindgen::JsCast;
+///
+/// #[component]
+/// fn Menu() -> View {
+///     let (open, set_open) = state(self, || true);
+///     let set_open = set_open.clone();
+///     let menu = node_ref::<HtmlDivElement>(self);
+///     let contains = menu.clone();
+///     document_event(self, "click", move |e: MouseEvent| {
+///         let target = e.target().and_then(|target| target.dyn_into::<Node>().ok());
+///         if let Some(menu) = contains.get() {
+///             if !menu.contains(target.as_ref()) {
+///                 set_open.set(false);
+///             }
+///         }
+///     });
+///     Div(
+///         self,
+///         node_ref = menu,
+///         hidden = !tracked!(open),
+///         Text(self, "Menu")
+///     )
+/// }
+/// ```
+#[track_caller]
+pub fn document_event<E: JsCast>(
+    ctx: HookContext,
+    event: &'static str,
+    handler: impl Fn(E) + 'static,
+) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    global_event(ctx, &document, event, handler);
+}
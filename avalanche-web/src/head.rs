@@ -0,0 +1,282 @@
+//! Hooks for setting the document's title and its `<meta>` and `<link>` tags.
+//!
+//! Each hook sets the value of a key: the title, a `<meta>` tag's `name` or `property`, or a `<link>`
+//! tag's `rel`. When several mounted components set the same key, the deepest one wins, as
+//! given by [HookContext::depth], and among equally deep ones, the one mounted last. Once a component
+//! unmounts, the value it set is reverted to the one set by the next winning component, or to the
+//! document's original value if there is none. Tags the document did not already contain are created as needed, and removed once
+//! no component sets them.
+//!
+//! ```rust
+//! use avalanche::{component, tracked, View};
+//! use avalanche_web::components::{H1, Text};
+//! use avalanche_web::head;
+//!
+//! #[component]
+//! fn Article(title: &str, summary: &str) -> View {
+//!     head::title(self, &format!("{} | Blog", tracked!(title)));
+//!     head::meta(self, "description", tracked!(summary));
+//!     head::property(self, "og:title", tracked!(title));
+//!     head::link(self, "canonical", "https://example.com/articles/1");
+//!     H1(self, Text(self, tracked!(title)))
+//! }
+//! ```
+//!
+//! Like [state], each hook stores its key per call site, so it must be called with a context
+//! from [HookContext::with_key](avalanche::hooks::HookContext::with_key) within a loop or an iterator closure.
+
+use std::cell::RefCell;
+
+use avalanche::hooks::HookContext;
+use avalanche::{state, tracked};
+use rustc_hash::FxHashMap;
+use wasm_bindgen::JsCast;
+use web_sys::Element;
+
+thread_local! {
+    static HEAD: RefCell<Head> = RefCell::new(Head::default());
+}
+
+/// What a head hook sets.
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum HeadKey {
+    Title,
+    /// The `content` of the `<meta>` tag whose attribute `attr` is `key`.
+    Meta { attr: &'static str, key: String },
+    /// The `href` of the `<link>` tag with the given `rel`.
+    Link { rel: String },
+}
+
+impl HeadKey {
+    /// Returns the tag name, the attribute identifying the tag, and the attribute holding its value.
+    fn tag(&self) -> Option<(&'static str, &'static str, &str, &'static str)> {
+        match self {
+            HeadKey::Title => None,
+            HeadKey::Meta { attr, key } => Some(("meta", attr, key, "content")),
+            HeadKey::Link { rel } => Some(("link", "rel", rel, "href")),
+        }
+    }
+}
+
+/// A value set for a key by a mounted component.
+struct HeadValue {
+    /// The id of the entry setting the value, which increases in mount order.
+    id: u64,
+    /// The depth of the component setting the value.
+    depth: usize,
+    value: String,
+}
+
+/// The values set for a key, along with what to restore once none are.
+struct Slot {
+    /// The values set by mounted components. The deepest, most recently mounted one is applied.
+    values: Vec<HeadValue>,
+    /// The title, or the tag's value attribute, before any component set it.
+    original: Option<String>,
+    /// The tag holding the value and the name of its value attribute, if the key is for a tag.
+    element: Option<(Element, &'static str)>,
+    /// Whether `element` was created for the key, rather than already in the document.
+    created: bool,
+}
+
+#[derive(Default)]
+struct Head {
+    slots: FxHashMap<HeadKey, Slot>,
+    next_id: u64,
+}
+
+impl Head {
+    fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id - 1
+    }
+
+    fn insert(&mut self, key: HeadKey, id: u64, depth: usize, value: &str) {
+        let slot = self
+            .slots
+            .entry(key.clone())
+            .or_insert_with(|| Slot::new(&key));
+        slot.values.push(HeadValue {
+            id,
+            depth,
+            value: value.to_owned(),
+        });
+        if slot.applied_id() == Some(id) {
+            slot.apply();
+        }
+    }
+
+    fn update(&mut self, key: &HeadKey, id: u64, value: &str) {
+        let slot = self.slots.get_mut(key).unwrap();
+        let entry = slot.values.iter_mut().find(|entry| entry.id == id).unwrap();
+        if entry.value != value {
+            entry.value = value.to_owned();
+            if slot.applied_id() == Some(id) {
+                slot.apply();
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &HeadKey, id: u64) {
+        let slot = self.slots.get_mut(key).unwrap();
+        let was_applied = slot.applied_id() == Some(id);
+        slot.values.retain(|entry| entry.id != id);
+        if slot.values.is_empty() {
+            let slot = self.slots.remove(key).unwrap();
+            slot.restore();
+        } else if was_applied {
+            slot.apply();
+        }
+    }
+}
+
+impl Slot {
+    fn new(key: &HeadKey) -> Self {
+        let document = web_sys::window().unwrap().document().unwrap();
+        match key.tag() {
+            None => Self {
+                values: Vec::new(),
+                original: Some(document.title()),
+                element: None,
+                created: false,
+            },
+            Some((tag, attr, key, value_attr)) => {
+                let head = document.head().expect("document has a head");
+                let existing = head.query_selector_all(tag).ok().and_then(|tags| {
+                    (0..tags.length())
+                        .filter_map(|i| tags.item(i))
+                        .map(JsCast::unchecked_into::<Element>)
+                        .find(|element| element.get_attribute(attr).as_deref() == Some(key))
+                });
+                match existing {
+                    Some(element) => Self {
+                        values: Vec::new(),
+                        original: element.get_attribute(value_attr),
+                        element: Some((element, value_attr)),
+                        created: false,
+                    },
+                    None => {
+                        let element = document.create_element(tag).unwrap();
+                        element.set_attribute(attr, key).unwrap();
+                        head.append_child(&element).unwrap();
+                        Self {
+                            values: Vec::new(),
+                            original: None,
+                            element: Some((element, value_attr)),
+                            created: true,
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the value to apply: the deepest one, and among those, the most recently mounted.
+    fn applied(&self) -> Option<&HeadValue> {
+        self.values.iter().max_by_key(|entry| (entry.depth, entry.id))
+    }
+
+    fn applied_id(&self) -> Option<u64> {
+        self.applied().map(|entry| entry.id)
+    }
+
+    /// Sets the applied value in the document.
+    fn apply(&self) {
+        if let Some(entry) = self.applied() {
+            self.set(Some(&entry.value));
+        }
+    }
+
+    /// Reverts the document to its state before any component set the key.
+    fn restore(self) {
+        match &self.element {
+            Some((element, _)) if self.created => element.remove(),
+            _ => self.set(self.original.as_deref()),
+        }
+    }
+
+    fn set(&self, value: Option<&str>) {
+        match (&self.element, value) {
+            (None, value) => web_sys::window()
+                .unwrap()
+                .document()
+                .unwrap()
+                .set_title(value.unwrap_or_default()),
+            (Some((element, value_attr)), Some(value)) => {
+                element.set_attribute(value_attr, value).unwrap()
+            }
+            (Some((element, value_attr)), None) => element.remove_attribute(value_attr).unwrap(),
+        }
+    }
+}
+
+/// A value set by a mounted component, removed once the component unmounts.
+struct HeadEntry {
+    key: HeadKey,
+    id: u64,
+}
+
+impl Drop for HeadEntry {
+    fn drop(&mut self) {
+        // the head may already be gone if the thread is exiting
+        let _ = HEAD.try_with(|head| head.borrow_mut().remove(&self.key, self.id));
+    }
+}
+
+#[track_caller]
+fn head_entry(ctx: HookContext, key: HeadKey, value: &str) {
+    let depth = ctx.depth();
+    let (entry, _) = state(ctx, RefCell::<Option<HeadEntry>>::default);
+    let mut entry = tracked!(entry).borrow_mut();
+    match &*entry {
+        Some(current) if current.key == key => {
+            HEAD.with(|head| head.borrow_mut().update(&key, current.id, value));
+        }
+        _ => {
+            // an entry whose key changed keeps its id, and so its priority over equally deep entries
+            let id = match entry.take() {
+                // dropping the previous entry removes its value, which accesses the head
+                Some(previous) => previous.id,
+                None => HEAD.with(|head| head.borrow_mut().next_id()),
+            };
+            HEAD.with(|head| head.borrow_mut().insert(key.clone(), id, depth, value));
+            *entry = Some(HeadEntry { key, id });
+        }
+    }
+}
+
+/// A hook setting the document's title.
+#[track_caller]
+pub fn title(ctx: HookContext, title: &str) {
+    head_entry(ctx, HeadKey::Title, title);
+}
+
+/// A hook setting the `content` of the `<meta>` tag with the given `name`, like `description`.
+#[track_caller]
+pub fn meta(ctx: HookContext, name: &str, content: &str) {
+    let key = HeadKey::Meta {
+        attr: "name",
+        key: name.to_owned(),
+    };
+    head_entry(ctx, key, content);
+}
+
+/// A hook setting the `content` of the `<meta>` tag with the given `property`, like the
+/// [Open Graph](https://ogp.me/) property `og:title`.
+#[track_caller]
+pub fn property(ctx: HookContext, property: &str, content: &str) {
+    let key = HeadKey::Meta {
+        attr: "property",
+        key: property.to_owned(),
+    };
+    head_entry(ctx, key, content);
+}
+
+/// A hook setting the `href` of the `<link>` tag with the given `rel`, like `canonical` or `icon`.
+#[track_caller]
+pub fn link(ctx: HookContext, rel: &str, href: &str) {
+    let key = HeadKey::Link {
+        rel: rel.to_owned(),
+    };
+    head_entry(ctx, key, href);
+}
@@ -1,41 +1,92 @@
 use avalanche::renderer::{
-    DispatchNativeEvent, NativeEvent, NativeHandle, Renderer, Scheduler,
+    DispatchNativeEvent, NativeEvent, NativeHandle, Priority, Renderer, Scheduler,
 };
-use avalanche::shared::Shared;
 use avalanche::vdom::Root;
 use avalanche::DefaultComponent;
 
-use clru::CLruCache;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::cell::{Cell, RefCell};
 use std::collections::VecDeque;
-use std::num::NonZeroUsize;
+use std::rc::Rc;
 
-use gloo_events::{EventListener, EventListenerOptions};
+use gloo_events::{EventListener, EventListenerOptions, EventListenerPhase};
+use wasm_bindgen::closure::Closure;
 use wasm_bindgen::{JsCast, JsValue, intern};
-use web_sys::{Element, EventTarget};
+use web_sys::{Element, EventTarget, IdleDeadline};
 
 pub mod bridge;
+pub mod class_list;
+mod commands;
 pub mod components;
+mod delegation;
 pub mod events;
-
-use crate::events::Event;
+mod global_event;
+pub mod head;
+mod node_ref;
+mod observer;
+mod string_cache;
+pub mod style;
+mod virtual_list;
+
+pub use global_event::{document_event, window_event};
+pub use node_ref::{node_ref, NodeRef};
+pub use observer::{element_size, intersection, media_query, Intersection, Size};
+pub use string_cache::StringCacheStats;
+pub use virtual_list::VirtualList;
+
+use crate::commands::{CommandBuffer, NodeId};
+use crate::delegation::{is_delegated, DelegateGuard, DelegatedHandler, Delegator};
+use crate::events::{Event, ListenerOptions};
+use crate::node_ref::RawNodeRef;
+use crate::string_cache::StringCache;
 
 static TIMEOUT_MSG_NAME: &str = "avalanche_web_message";
 
+/// Options for rendering a component, passed to [mount_with_options].
+#[derive(Clone)]
+pub struct MountOptions {
+    /// The number of strings, like text and attribute values, cached in JS to avoid resending them.
+    /// Tag and attribute names are always kept, and do not count towards this capacity.
+    /// Must not be zero; defaults to `64`.
+    pub string_cache_capacity: usize,
+    /// The length in bytes above which strings are not cached, as long strings are rarely reused.
+    /// Defaults to `256`.
+    pub max_cached_string_len: usize,
+    /// Counts of string cache lookups, updated as the component renders.
+    pub string_cache_stats: StringCacheStats,
+}
+
+impl Default for MountOptions {
+    fn default() -> Self {
+        Self {
+            string_cache_capacity: 64,
+            max_cached_string_len: 256,
+            string_cache_stats: StringCacheStats::default(),
+        }
+    }
+}
+
 /// Renders the given component onto the `element` parameter.
 ///
 /// To unmount the component, use the returned [Root].
 pub fn mount<C: DefaultComponent>(element: Element) -> Root {
-    let renderer = WebRenderer::new();
-    let scheduler = WebScheduler::new();
+    mount_with_options::<C>(element, MountOptions::default())
+}
 
-    // Clear children of the mount element to ensure children modification
-    // indices are consistent with internal state
-    bridge::truncate_children(element.unchecked_ref(), 0);
+/// Like [mount], but with the given `options`.
+pub fn mount_with_options<C: DefaultComponent>(element: Element, options: MountOptions) -> Root {
+    let scheduler = WebScheduler::new();
+    let renderer = WebRenderer::new(&element, scheduler.queue.clone(), options);
 
     let native_parent_handle = WebNativeHandle {
+        id: renderer.register_node(&element),
         node: element.into(),
-        _listeners: Default::default(),
+        attributes: Default::default(),
+        listeners: Default::default(),
+        node_ref: Default::default(),
+        styles: Default::default(),
+        classes: Default::default(),
+        text: Default::default(),
     };
 
     let root = avalanche::vdom::Root::new::<_, _, C>(
@@ -60,75 +111,302 @@ pub fn mount_to_body<C: DefaultComponent>() -> Root {
     mount::<C>(body.into())
 }
 
-struct WebScheduler {
+/// Queues of functions to run on the ui thread, one per priority, shared by the scheduler and the renderer.
+///
+/// Each queue is run by a different browser callback:
+/// - [Immediate](Priority::Immediate) functions run in a microtask, once the running code completes;
+/// - [Normal](Priority::Normal) functions run in a task posted with `postMessage`, after pending events;
+/// - [Animation](Priority::Animation) functions run in a `requestAnimationFrame` callback;
+/// - [Idle](Priority::Idle) functions run in a `requestIdleCallback` callback, or a timeout where unsupported.
+///
+/// A single callback runs every function queued before it, leaving those queued while it runs
+/// to the next callback, and idle callbacks stop once the browser is no longer idle.
+#[derive(Clone)]
+struct UiQueue {
+    state: Rc<UiQueueState>,
+}
+
+struct UiQueueState {
     window: web_sys::Window,
-    queued_fns: Shared<VecDeque<Box<dyn FnOnce()>>>,
-    _listener: EventListener,
+    immediate: Lane,
+    normal: Lane,
+    animation: Lane,
+    idle: Lane,
+    /// Set once the state is shared, as the callbacks refer back to it.
+    callbacks: RefCell<Option<LaneCallbacks>>,
 }
 
-impl WebScheduler {
+#[derive(Default)]
+struct Lane {
+    fns: RefCell<VecDeque<Box<dyn FnOnce()>>>,
+    /// Whether a callback running the lane is pending.
+    requested: Cell<bool>,
+}
+
+/// The browser callbacks running each lane.
+struct LaneCallbacks {
+    immediate: Closure<dyn FnMut()>,
+    animation: Closure<dyn FnMut()>,
+    idle: Closure<dyn FnMut(JsValue)>,
+    _message: EventListener,
+}
+
+impl UiQueue {
     fn new() -> Self {
         let window = web_sys::window().unwrap();
-        let queued_fns = Shared::default();
-        let queued_fns_clone = queued_fns.clone();
+        let queue = UiQueue {
+            state: Rc::new(UiQueueState {
+                window: window.clone(),
+                immediate: Lane::default(),
+                normal: Lane::default(),
+                animation: Lane::default(),
+                idle: Lane::default(),
+                callbacks: RefCell::new(None),
+            }),
+        };
+
+        // weak references keep the callbacks from owning the state that owns them
+        let weak = Rc::downgrade(&queue.state);
+        let run = move |priority: Priority, deadline: Option<IdleDeadline>| {
+            if let Some(state) = weak.upgrade() {
+                UiQueue { state }.run(priority, deadline);
+            }
+        };
+        let run_immediate = run.clone();
+        let run_animation = run.clone();
+        let run_idle = run.clone();
 
         // sets up fast execution of 0ms timeouts
         // uses approach in https://dbaron.org/log/20100309-faster-timeouts
-        let _listener = EventListener::new(&window, "message", move |e| {
+        let _message = EventListener::new(&window, "message", move |e| {
             let e = e.clone();
             if let Ok(event) = e.dyn_into::<web_sys::MessageEvent>() {
                 if event.data() == TIMEOUT_MSG_NAME {
                     event.stop_propagation();
-                    // f may call schedule_on_ui_thread, so it must be called outside of exec_mut
-                    let f = queued_fns_clone
-                        .exec_mut(|queue: &mut VecDeque<Box<dyn FnOnce()>>| queue.pop_front());
-                    if let Some(f) = f {
-                        f();
-                    }
+                    run(Priority::Normal, None);
                 }
             }
         });
 
+        *queue.state.callbacks.borrow_mut() = Some(LaneCallbacks {
+            immediate: Closure::wrap(Box::new(move || run_immediate(Priority::Immediate, None))),
+            animation: Closure::wrap(Box::new(move || run_animation(Priority::Animation, None))),
+            // the deadline is undefined when falling back to a timeout
+            idle: Closure::wrap(Box::new(move |deadline: JsValue| {
+                run_idle(Priority::Idle, deadline.dyn_into().ok())
+            })),
+            _message,
+        });
+        queue
+    }
+
+    fn lane(&self, priority: Priority) -> &Lane {
+        match priority {
+            Priority::Immediate => &self.state.immediate,
+            Priority::Normal => &self.state.normal,
+            Priority::Animation => &self.state.animation,
+            Priority::Idle => &self.state.idle,
+        }
+    }
+
+    fn schedule(&self, f: Box<dyn FnOnce()>, priority: Priority) {
+        let lane = self.lane(priority);
+        lane.fns.borrow_mut().push_back(f);
+        if !lane.requested.replace(true) {
+            self.request(priority);
+        }
+    }
+
+    /// Requests the browser callback running the lane for `priority`.
+    fn request(&self, priority: Priority) {
+        let callbacks = self.state.callbacks.borrow();
+        let callbacks = callbacks.as_ref().expect("lane callbacks");
+        let window = &self.state.window;
+        match priority {
+            Priority::Immediate => bridge::queue_microtask(&callbacks.immediate),
+            Priority::Normal => window.post_message(&TIMEOUT_MSG_NAME.into(), "*").unwrap(),
+            Priority::Animation => {
+                window
+                    .request_animation_frame(callbacks.animation.as_ref().unchecked_ref())
+                    .unwrap();
+            }
+            Priority::Idle => {
+                let callback = callbacks.idle.as_ref().unchecked_ref();
+                if window.request_idle_callback(callback).is_err() {
+                    window.set_timeout_with_callback(callback).unwrap();
+                }
+            }
+        }
+    }
+
+    /// Runs the functions queued with `priority`, stopping early once `deadline` has passed.
+    fn run(&self, priority: Priority, deadline: Option<IdleDeadline>) {
+        let lane = self.lane(priority);
+        // functions queued while the lane runs, as by a function rescheduling itself, run in the
+        // next callback, so that the browser gets control back in between
+        let mut remaining = lane.fns.borrow().len();
+        while remaining > 0 && deadline.as_ref().map_or(true, |deadline| deadline.time_remaining() > 0.0) {
+            // f may call schedule_on_ui_thread, so the queue must not be borrowed while it runs
+            let f = lane.fns.borrow_mut().pop_front();
+            match f {
+                Some(f) => f(),
+                None => break,
+            }
+            remaining -= 1;
+        }
+        lane.requested.set(false);
+        if !lane.fns.borrow().is_empty() {
+            lane.requested.set(true);
+            self.request(priority);
+        }
+    }
+}
+
+struct WebScheduler {
+    queue: UiQueue,
+}
+
+impl WebScheduler {
+    fn new() -> Self {
         WebScheduler {
-            window,
-            queued_fns,
-            _listener,
+            queue: UiQueue::new(),
         }
     }
 }
 
 impl Scheduler for WebScheduler {
-    fn schedule_on_ui_thread(&mut self, f: Box<dyn FnOnce()>) {
-        // post message for 0ms timeouts
-        // technique from https://dbaron.org/log/20100309-faster-timeouts
-        self.queued_fns.exec_mut(move |queue| {
-            queue.push_back(f);
-        });
-        self.window
-            .post_message(&TIMEOUT_MSG_NAME.into(), "*")
-            .unwrap();
+    fn schedule_on_ui_thread(&mut self, f: Box<dyn FnOnce()>, priority: Priority) {
+        self.queue.schedule(f, priority);
     }
 }
 
 struct WebNativeHandle {
+    /// The id buffered commands refer to `node` by.
+    id: NodeId,
     node: web_sys::Node,
-    _listeners: FxHashMap<&'static str, EventListener>,
+    /// The names of the attributes currently set on the element.
+    attributes: RefCell<FxHashSet<&'static str>>,
+    listeners: RefCell<Listeners>,
+    /// The ref filled in with `node`, if any.
+    node_ref: RefCell<Option<RawNodeRef>>,
+    /// The inline style properties set through the `styles` parameter, and their values.
+    styles: RefCell<FxHashMap<String, String>>,
+    /// The classes added through the `classes` parameter.
+    classes: RefCell<FxHashSet<String>>,
+    /// The text of a text node, as last rendered.
+    text: RefCell<String>,
 }
 
-struct WebRenderer {
-    string_cache: CLruCache<String, u32>,
+impl Drop for WebNativeHandle {
+    fn drop(&mut self) {
+        // Empty the ref, unless it has since been passed to another element
+        if let Some(node_ref) = self.node_ref.get_mut() {
+            let mut node = node_ref.borrow_mut();
+            if node.as_ref() == Some(&self.node) {
+                *node = None;
+            }
+        }
+    }
 }
 
-const STRING_CACHE_CAPACITY: usize = 64;
+/// The event handlers attached to a native element.
+#[derive(Default)]
+struct Listeners {
+    /// Dispatches events to the element's component; `None` for nodes without handlers.
+    dispatcher: Option<DispatchNativeEvent>,
+    /// The event and options each handler was attached with, keyed by handler name.
+    attached: FxHashMap<&'static str, (&'static str, ListenerOptions)>,
+    /// Listeners on the element itself, keyed by handler name.
+    native: FxHashMap<&'static str, EventListener>,
+    /// The element's registration for delegated handlers, if it has had any.
+    delegate: Option<DelegateGuard>,
+}
+
+impl Listeners {
+    fn new(dispatcher: DispatchNativeEvent) -> Self {
+        Self {
+            dispatcher: Some(dispatcher),
+            ..Default::default()
+        }
+    }
+
+    /// Attaches the handler `name` for `event`, replacing any handler previously
+    /// attached under that name with different options.
+    fn attach(
+        &mut self,
+        delegator: &Delegator,
+        element: &Element,
+        name: &'static str,
+        event: &'static str,
+        options: ListenerOptions,
+    ) {
+        if self.attached.get(name) == Some(&(event, options)) {
+            return;
+        }
+        self.detach(delegator, name);
+
+        let dispatcher = self.dispatcher.as_ref().expect("dispatcher for element with handlers");
+        if is_delegated(event, options) {
+            let delegate = self
+                .delegate
+                .get_or_insert_with(|| delegator.register(element, dispatcher.clone()));
+            delegator.add_handler(
+                delegate,
+                DelegatedHandler {
+                    event,
+                    capture: options.capture,
+                    name,
+                },
+            );
+        } else {
+            add_listener(
+                element,
+                event,
+                name,
+                options,
+                create_handler(name, dispatcher.clone()),
+                &mut self.native,
+            );
+        }
+        self.attached.insert(name, (event, options));
+    }
+
+    /// Detaches the handler `name`, if attached.
+    fn detach(&mut self, delegator: &Delegator, name: &'static str) {
+        if let Some((event, options)) = self.attached.remove(name) {
+            if is_delegated(event, options) {
+                if let Some(delegate) = &self.delegate {
+                    delegator.remove_handler(delegate, event, options.capture);
+                }
+            } else {
+                self.native.remove(name);
+            }
+        }
+    }
+}
+
+struct WebRenderer {
+    strings: StringCache,
+    commands: CommandBuffer,
+    delegator: Delegator,
+    ui_queue: UiQueue,
+}
 
 impl WebRenderer {
-    fn new() -> Self {
+    fn new(root: &Element, ui_queue: UiQueue, options: MountOptions) -> Self {
         // Intern string data for sending window messages
         intern("*");
         intern(TIMEOUT_MSG_NAME);
 
         WebRenderer {
-            string_cache: CLruCache::new(NonZeroUsize::new(STRING_CACHE_CAPACITY).unwrap()),
+            strings: StringCache::new(
+                options.string_cache_capacity,
+                options.max_cached_string_len,
+                options.string_cache_stats,
+            ),
+            commands: CommandBuffer::new(),
+            delegator: Delegator::new(root.clone()),
+            ui_queue,
         }
     }
 
@@ -139,37 +417,105 @@ impl WebRenderer {
     }
 
     /// Looks up the given string in the string cache.
-    // TODO: don't cache giant strings in lookup LRU
     pub(crate) fn string_idx(&mut self, string: &str) -> u32 {
-        if string.is_empty() {
-            return 0;
-        }
-        match self.string_cache.get(string) {
-            Some(idx) => *idx,
-            None => {
-                // If we've reached capacity, evict the least-used entry from 
-                // bridge string cache, and insert new string there. Otherwise,
-                // place the string at the next valid index
-                let idx = if self.string_cache.len() == STRING_CACHE_CAPACITY {
-                    let value_to_insert_at = *self.string_cache.back().unwrap().1;
-                    value_to_insert_at
-                } else {
-                    self.string_cache.len() as u32 + 1
-                };
-                // TODO: reuse allocation?
-                let encoded_string: Vec<u16> = string.encode_utf16().collect();
-                self.string_cache.put(string.to_owned(), idx);
-                bridge::intern_string_at(&encoded_string, idx);
-                idx
+        self.strings.idx(string, &self.commands)
+    }
+
+    /// Looks up the given tag or attribute name, which is kept in the string cache once sent.
+    pub(crate) fn name_idx(&mut self, name: &'static str) -> u32 {
+        self.strings.pinned_idx(name)
+    }
+
+    /// Adds the existing node `node` to the node table.
+    pub(crate) fn register_node(&self, node: &web_sys::Node) -> NodeId {
+        let id = self.commands.node_id();
+        bridge::set_node(id.get(), node);
+        id
+    }
+
+    /// Creates a text node containing `text`.
+    pub(crate) fn create_text_node(&mut self, text: &str) -> (NodeId, web_sys::Text) {
+        let text_idx = self.string_idx(text);
+        let id = self.commands.node_id();
+        let node = bridge::create_text_node(id.get(), text_idx);
+        (id, node.unchecked_into())
+    }
+
+    /// Creates an element with the given tag, within `namespace` if given.
+    pub(crate) fn create_element(
+        &mut self,
+        tag: &'static str,
+        namespace: Option<&'static str>,
+    ) -> (NodeId, Element) {
+        let tag_idx = self.name_idx(tag);
+        let id = self.commands.node_id();
+        let element = match namespace {
+            Some(namespace) => {
+                let namespace_idx = self.name_idx(namespace);
+                bridge::create_element_ns(id.get(), namespace_idx, tag_idx)
             }
-        }
+            None => bridge::create_element(id.get(), tag_idx),
+        };
+        (id, element.unchecked_into())
+    }
+
+    /// Sets the text of the text node `node`.
+    pub(crate) fn set_text_content(&mut self, node: u32, text: &str) {
+        let text_idx = self.string_idx(text);
+        self.commands.set_text_content(node, text_idx);
     }
     
-    pub(crate) fn set_attribute(&mut self, element: &JsValue, name: &str, value: &str) {
+    /// Sets the attribute `name` on `element`, removing it if `value` is empty.
+    /// Attributes with an `xlink:` or `xml:` prefix are set within their namespace.
+    pub(crate) fn set_attribute(&mut self, element: u32, name: &'static str, value: &str) {
+        let name_idx = self.name_idx(name);
+        let value_idx = self.string_idx(value);
+        match attribute_namespace(name) {
+            Some(namespace) => {
+                let namespace_idx = self.name_idx(namespace);
+                self.commands.set_attribute_ns(element, namespace_idx, name_idx, value_idx);
+            }
+            None => self.commands.set_attribute(element, name_idx, value_idx),
+        }
+    }
+
+    /// Sets the inline style property `name` on `element`.
+    pub(crate) fn set_style_property(&mut self, element: u32, name: &str, value: &str) {
         let name_idx = self.string_idx(name);
         let value_idx = self.string_idx(value);
-        bridge::set_attribute(element, name_idx, value_idx);
+        self.commands.set_style_property(element, name_idx, value_idx);
+    }
 
+    /// Removes the inline style property `name` from `element`.
+    pub(crate) fn remove_style_property(&mut self, element: u32, name: &str) {
+        let name_idx = self.string_idx(name);
+        self.commands.remove_style_property(element, name_idx);
+    }
+
+    /// Selects the options of the select element `element` whose values are in `values`,
+    /// including options added to it later.
+    pub(crate) fn set_select_values<'a>(&mut self, element: u32, values: impl IntoIterator<Item = &'a str>) {
+        let value_idxs: Vec<u32> = values.into_iter().map(|value| self.string_idx(value)).collect();
+        self.commands.set_select_values(element, &value_idxs);
+    }
+
+    /// Adds the class `name` to `element`'s class list.
+    pub(crate) fn add_class(&mut self, element: u32, name: &str) {
+        let name_idx = self.string_idx(name);
+        self.commands.add_class(element, name_idx);
+    }
+
+    /// Removes the class `name` from `element`'s class list.
+    pub(crate) fn remove_class(&mut self, element: u32, name: &str) {
+        let name_idx = self.string_idx(name);
+        self.commands.remove_class(element, name_idx);
+    }
+}
+
+impl Drop for WebRenderer {
+    fn drop(&mut self) {
+        // Release the nodes of handles dropped after the last render
+        self.flush();
     }
 }
 
@@ -179,9 +525,9 @@ impl Renderer for WebRenderer {
         parent_handle: &NativeHandle,
         child_handle: &NativeHandle,
     ) {
-        let parent_node = &Self::handle_cast(parent_handle).node;
-        let child_node = &Self::handle_cast(child_handle).node;
-        bridge::append_child(parent_node, child_node);
+        let parent = Self::handle_cast(parent_handle).id.get();
+        let child = Self::handle_cast(child_handle).id.get();
+        self.commands.append_child(parent, child);
     }
 
     fn insert_child(
@@ -190,9 +536,9 @@ impl Renderer for WebRenderer {
         index: usize,
         child_handle: &NativeHandle,
     ) {
-        let parent_node = &Self::handle_cast(parent_handle).node;
-        let child_node = &Self::handle_cast(child_handle).node;
-        bridge::insert_child(parent_node, index as u32, child_node)
+        let parent = Self::handle_cast(parent_handle).id.get();
+        let child = Self::handle_cast(child_handle).id.get();
+        self.commands.insert_child(parent, index as u32, child);
     }
 
     fn swap_children(
@@ -201,13 +547,13 @@ impl Renderer for WebRenderer {
         a: usize,
         b: usize,
     ) {
-        let parent_node = &Self::handle_cast(parent_handle).node;
+        let parent = Self::handle_cast(parent_handle).id.get();
         let lesser_idx = std::cmp::min(a, b);
         let greater_idx = std::cmp::max(a, b);
 
         // TODO: throw exception if a and b are equal but out of bounds?
         if a != b {
-            bridge::swap_children(parent_node, lesser_idx as u32, greater_idx as u32);
+            self.commands.swap_children(parent, lesser_idx as u32, greater_idx as u32);
         }
     }
 
@@ -237,8 +583,12 @@ impl Renderer for WebRenderer {
         parent_handle: &NativeHandle,
         len: usize,
     ) {
-        let parent_node = &Self::handle_cast(parent_handle).node;
-        bridge::truncate_children(parent_node, len as u32);
+        let parent = Self::handle_cast(parent_handle).id.get();
+        self.commands.truncate_children(parent, len as u32);
+    }
+
+    fn flush(&mut self) {
+        self.strings.flush(&self.commands);
     }
 
     // fn remove_child(
@@ -261,35 +611,45 @@ impl Renderer for WebRenderer {
     }
 }
 
-fn add_listener(
-    element: &web_sys::Element,
-    name: &'static str,
-    callback: impl Fn(Event) + 'static,
-    listeners: &mut FxHashMap<&'static str, EventListener>,
-) {
-    add_named_listener(element, name, name, false, callback, listeners)
+/// Returns the namespace of attributes with a reserved prefix, like `xlink:href`.
+fn attribute_namespace(name: &str) -> Option<&'static str> {
+    match name.split_once(':')?.0 {
+        "xlink" => Some(components::svg::XLINK_NAMESPACE),
+        "xml" => Some(components::svg::XML_NAMESPACE),
+        _ => None,
+    }
 }
 
-fn add_named_listener(
+fn add_listener(
     element: &web_sys::Element,
     event: &'static str,
     name: &'static str,
-    passive: bool,
+    options: ListenerOptions,
     callback: impl Fn(Event) + 'static,
     listeners: &mut FxHashMap<&'static str, EventListener>,
 ) {
-    let options = EventListenerOptions {
-        passive,
-        ..Default::default()
+    let gloo_options = EventListenerOptions {
+        phase: if options.capture {
+            EventListenerPhase::Capture
+        } else {
+            EventListenerPhase::Bubble
+        },
+        passive: options.passive,
     };
     
     // reduce overhead of passing event names etc to JS
     intern(event);
     intern(name);
 
-    let listener = EventListener::new_with_options(element, event, options, move |event| {
-        callback(event.clone())
-    });
+    let listener = if options.once {
+        EventListener::once_with_options(element, event, gloo_options, move |event| {
+            callback(event.clone())
+        })
+    } else {
+        EventListener::new_with_options(element, event, gloo_options, move |event| {
+            callback(event.clone())
+        })
+    };
     listeners.insert(name, listener);
 }
 
@@ -0,0 +1,86 @@
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use avalanche::hooks::HookContext;
+use avalanche::{state, tracked};
+use wasm_bindgen::JsCast;
+use web_sys::Node;
+
+/// The untyped storage shared between a [`NodeRef`] and the element it refers to.
+pub(crate) type RawNodeRef = Rc<RefCell<Option<Node>>>;
+
+/// A reference to the native DOM element of a component, typically created with [`node_ref`].
+///
+/// Passing a `NodeRef` to an element's `node_ref` parameter fills it in with the element once
+/// the element is created, and empties it once the element is removed. `T` is the `web_sys`
+/// type of the element, like `web_sys::HtmlInputElement` for an `Input`.
+pub struct NodeRef<T> {
+    pub(crate) node: RawNodeRef,
+    _marker: PhantomData<T>,
+}
+
+impl<T: JsCast> NodeRef<T> {
+    /// Creates an empty `NodeRef`.
+    pub fn new() -> Self {
+        Self {
+            node: Default::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the referenced element, or `None` if it has not been created yet or has been removed.
+    pub fn get(&self) -> Option<T> {
+        self.node.borrow().clone().map(JsCast::unchecked_into)
+    }
+}
+
+impl<T: JsCast> Default for NodeRef<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for NodeRef<T> {
+    fn clone(&self) -> Self {
+        Self {
+            node: self.node.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// A hook returning a [`NodeRef`] that persists across renders.
+///
+/// Like [state], `node_ref` stores its value per call site, so it must be called with a context
+/// from [HookContext::with_key](avalanche::hooks::HookContext::with_key) within a loop or an iterator closure.
+///
+/// # Example
+/// ```rust
+/// use avalanche::{component, View};
+/// use avalanche_web::components::{Button, Div, Input, Text};
+/// use avalanche_web::node_ref;
+///
+/// #[component]
+/// fn FocusInput() -> View {
+///     let input = node_ref(self);
+///     let focus = input.clone();
+///     Div(self, [
+///         Input(self, node_ref = input),
+///         Button(
+///             self,
+///             on_click = move |_| {
+///                 if let Some(input) = focus.get() {
+///                     input.focus().unwrap();
+///                 }
+///             },
+///             Text(self, "Focus")
+///         )
+///     ])
+/// }
+/// ```
+#[track_caller]
+pub fn node_ref<T: JsCast + 'static>(ctx: HookContext) -> NodeRef<T> {
+    let (node_ref, _) = state(ctx, NodeRef::new);
+    tracked!(node_ref).clone()
+}
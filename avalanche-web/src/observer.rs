@@ -0,0 +1,236 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use avalanche::hooks::HookContext;
+use avalanche::{state, state_keyed, tracked, Tracked};
+use gloo_events::EventListener;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsValue;
+use web_sys::Node;
+
+use crate::bridge;
+use crate::node_ref::{NodeRef, RawNodeRef};
+
+/// The size of an element's content box in CSS pixels, returned by [element_size].
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Size {
+    pub width: f64,
+    pub height: f64,
+}
+
+/// How much of an element is within the viewport, returned by [intersection].
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Intersection {
+    /// Whether the element intersects the viewport. This may be true while `ratio` is `0.0`,
+    /// as for an empty element at the edge of the viewport.
+    pub is_intersecting: bool,
+    /// The fraction of the element within the viewport, from `0.0` to `1.0`.
+    pub ratio: f64,
+}
+
+/// A `ResizeObserver` or `IntersectionObserver` watching a node, disconnected once dropped.
+struct NodeObserver {
+    node: Node,
+    observer: JsValue,
+    _callback: Closure<dyn FnMut(f64, f64)>,
+}
+
+impl Drop for NodeObserver {
+    fn drop(&mut self) {
+        bridge::disconnect(&self.observer);
+    }
+}
+
+struct Observed<T> {
+    value: T,
+    observer: Rc<RefCell<Option<NodeObserver>>>,
+}
+
+impl<T: Default> Default for Observed<T> {
+    fn default() -> Self {
+        Self {
+            value: T::default(),
+            observer: Default::default(),
+        }
+    }
+}
+
+/// Observes the node of `node_ref` with the observer created by `observe`, which calls back
+/// with two numbers converted to the returned value by `value`.
+#[track_caller]
+fn observe<'a, T: Default + 'static>(
+    ctx: HookContext<'a, '_>,
+    node_ref: &RawNodeRef,
+    observe: impl FnOnce(&JsValue, &Closure<dyn FnMut(f64, f64)>) -> JsValue + 'static,
+    value: fn(f64, f64) -> T,
+) -> Tracked<&'a T> {
+    let (observed, set_observed) = state(ctx, Observed::<T>::default);
+    let observer = Rc::downgrade(&tracked!(observed).observer);
+    let node_ref = node_ref.clone();
+    let set_observed = set_observed.clone();
+
+    // the node is only created or replaced once rendering completes, so it is observed after
+    let task = Closure::once_into_js(move || {
+        let observer = match observer.upgrade() {
+            Some(observer) => observer,
+            // the component was removed
+            None => return,
+        };
+        let mut observer = observer.borrow_mut();
+        let node = node_ref.borrow().clone();
+        if observer.as_ref().map(|observer| &observer.node) == node.as_ref() {
+            return;
+        }
+        *observer = node.map(|node| {
+            let callback = Closure::wrap(Box::new(move |a, b| {
+                let value = value(a, b);
+                set_observed.update(move |observed| observed.value = value);
+            }) as Box<dyn FnMut(f64, f64)>);
+            NodeObserver {
+                observer: observe(&node, &callback),
+                node,
+                _callback: callback,
+            }
+        });
+    });
+    bridge::queue_microtask_once(task);
+
+    observed.map(|observed| &observed.value)
+}
+
+/// A hook returning the size of the element referred to by `node_ref`, observed with a `ResizeObserver`.
+///
+/// The size is zero until the element is first measured, after it is created. Whenever the size
+/// changes, only the calling component is rerendered. Once the component is removed, the element
+/// is no longer observed. Like [state](avalanche::state), `element_size` must be called
+/// with a context from [HookContext::with_key](avalanche::hooks::HookContext::with_key) within a loop or an iterator closure.
+///
+/// # Example
+/// ```rust
+/// use avalanche::{component, tracked, View};
+/// use avalanche_web::components::{Div, Text};
+/// use avalanche_web::{element_size, node_ref};
+/// use web_sys::HtmlDivElement;
+///
+/// #[component]
+/// fn Panel() -> View {
+///     let panel = node_ref::<HtmlDivElement>(self);
+///     let size = element_size(self, &panel);
+///     let layout = if tracked!(size).width < 600.0 { "narrow" } else { "wide" };
+///     Div(self, node_ref = panel, class = tracked!(layout), Text(self, tracked!(layout)))
+/// }
+/// ```
+#[track_caller]
+pub fn element_size<T>(ctx: HookContext, node_ref: &NodeRef<T>) -> Tracked<Size> {
+    observe(
+        ctx,
+        &node_ref.node,
+        bridge::observe_resize,
+        |width, height| Size { width, height },
+    )
+    .map(|size| *size)
+}
+
+/// A hook returning how much of the element referred to by `node_ref` is within the viewport,
+/// observed with an `IntersectionObserver`.
+///
+/// The calling component is rerendered whenever the visible fraction of the element crosses one of
+/// `thresholds`, which are fractions from `0.0` to `1.0`, as well as when the element is first observed.
+/// The thresholds are read when the element is first observed, so later changes have no effect.
+/// Once the component is removed, the element is no longer observed. Like [state](avalanche::state),
+/// `intersection` must be called with a context from [HookContext::with_key](avalanche::hooks::HookContext::with_key) within a loop or an iterator closure.
+///
+/// # Example
+/// Loading an image once it scrolls into view:
+/// ```rust
+/// use avalanche::{component, tracked, View};
+/// use avalanche_web::components::{Div, Img};
+/// use avalanche_web::{intersection, node_ref};
+/// use web_sys::HtmlDivElement;
+///
+/// #[component]
+/// fn LazyImage(src: &str) -> View {
+///     let container = node_ref::<HtmlDivElement>(self);
+///     let visible = intersection(self, &container, &[0.0]);
+///     Div(
+///         self,
+///         node_ref = container,
+///         if tracked!(visible).is_intersecting {
+///             Img(self, src = tracked!(src))
+///         } else {
+///             ().into()
+///         }
+///     )
+/// }
+/// ```
+#[track_caller]
+pub fn intersection<T>(
+    ctx: HookContext,
+    node_ref: &NodeRef<T>,
+    thresholds: &[f64],
+) -> Tracked<Intersection> {
+    let thresholds = thresholds.to_vec();
+    observe(
+        ctx,
+        &node_ref.node,
+        move |node, callback| bridge::observe_intersection(node, &thresholds, callback),
+        |is_intersecting, ratio| Intersection {
+            is_intersecting: is_intersecting != 0.0,
+            ratio,
+        },
+    )
+    .map(|intersection| *intersection)
+}
+
+#[derive(Default)]
+struct MediaQuery {
+    matches: Cell<bool>,
+    listener: RefCell<Option<EventListener>>,
+}
+
+/// A hook returning whether the media query `query`, like `(max-width: 600px)`, matches.
+///
+/// Whenever the query starts or stops matching, only the calling component is rerendered.
+/// If `query` changes, the new query is evaluated and listened to within the same render.
+/// Like [state](avalanche::state), `media_query` must be called with a context from [HookContext::with_key](avalanche::hooks::HookContext::with_key)
+/// within a loop or an iterator closure, unless each iteration passes a different `query`.
+///
+/// # Example
+/// ```rust
+/// use avalanche::{component, tracked, View};
+/// use avalanche_web::components::Text;
+/// use avalanche_web::media_query;
+///
+/// #[component]
+/// fn Theme() -> View {
+///     let dark = media_query(self, "(prefers-color-scheme: dark)");
+///     Text(self, if tracked!(dark) { "dark" } else { "light" })
+/// }
+/// ```
+#[track_caller]
+pub fn media_query(ctx: HookContext, query: &str) -> Tracked<bool> {
+    // keying the state by the query gives each query its own listener, with the previous query's
+    // state destroyed once it is no longer called for
+    let (media_query, set_media_query) = state_keyed(ctx, query, MediaQuery::default);
+
+    let current = tracked!(media_query);
+    let mut listener = current.listener.borrow_mut();
+    if listener.is_none() {
+        let list = web_sys::window()
+            .unwrap()
+            .match_media(query)
+            .unwrap()
+            .expect("a media query list");
+        current.matches.set(list.matches());
+
+        let set_media_query = set_media_query.clone();
+        let list_clone = list.clone();
+        *listener = Some(EventListener::new(&list, "change", move |_| {
+            let matches = list_clone.matches();
+            set_media_query.update(move |media_query| media_query.matches.set(matches));
+        }));
+    }
+    drop(listener);
+
+    media_query.map(|media_query| media_query.matches.get())
+}
@@ -0,0 +1,366 @@
+//! The strings sent to JS, stored in a table on the JS side so commands can refer to them by index.
+//!
+//! Strings are stored in one of three ways:
+//! - tag, attribute, and namespace names come from a small, fixed set, so they are pinned
+//!   in the table for the lifetime of the renderer;
+//! - other strings up to a maximum length are kept in an LRU cache of configurable capacity;
+//! - longer strings, which are rarely sent twice, bypass the cache and are only stored until the next flush.
+//!
+//! Index `0` is reserved for the empty string, and indices `1..=capacity` for the LRU cache.
+//! Pinned and uncached strings take indices past those.
+
+use std::cell::Cell;
+use std::num::NonZeroUsize;
+use std::rc::Rc;
+
+use clru::CLruCache;
+use rustc_hash::FxHashMap;
+
+#[cfg(not(test))]
+use crate::bridge;
+use crate::commands::CommandBuffer;
+
+pub(crate) struct StringCache {
+    lru: CLruCache<String, u32>,
+    /// Whether each LRU index has been looked up since the last flush.
+    batched: Vec<bool>,
+    /// LRU indices looked up since the last command was pushed, which the next command
+    /// may refer to, so they must not be evicted.
+    pending: Vec<u32>,
+    /// The number of commands pushed when `pending` was last cleared.
+    pending_for: u64,
+    pinned: FxHashMap<&'static str, u32>,
+    /// Indices of uncached strings sent since the last flush.
+    uncached: Vec<u32>,
+    /// Indices past the LRU cache no longer in use.
+    free: Vec<u32>,
+    next_idx: u32,
+    max_cached_len: usize,
+    stats: StringCacheStats,
+    /// Reused buffer for UTF-16 encoding.
+    encoded: Vec<u16>,
+    /// The strings sent and their indices, recorded instead of sending them to JS in unit tests.
+    #[cfg(test)]
+    sent: Vec<(String, u32)>,
+}
+
+impl StringCache {
+    pub(crate) fn new(capacity: usize, max_cached_len: usize, stats: StringCacheStats) -> Self {
+        let capacity = NonZeroUsize::new(capacity).expect("nonzero string cache capacity");
+        Self {
+            lru: CLruCache::new(capacity),
+            batched: vec![false; capacity.get() + 1],
+            pending: Vec::new(),
+            pending_for: 0,
+            pinned: FxHashMap::default(),
+            uncached: Vec::new(),
+            free: Vec::new(),
+            next_idx: capacity.get() as u32 + 1,
+            max_cached_len,
+            stats,
+            encoded: Vec::new(),
+            #[cfg(test)]
+            sent: Vec::new(),
+        }
+    }
+
+    /// Returns the index of `string`, sending it to JS if needed. As commands buffered in
+    /// `commands` may refer to a string evicted to make room, they may be flushed first.
+    /// Indices returned since the last command was pushed to `commands` are not evicted,
+    /// so a command may look up all of its strings before it is pushed.
+    pub(crate) fn idx(&mut self, string: &str, commands: &CommandBuffer) -> u32 {
+        if string.is_empty() {
+            return 0;
+        }
+        if string.len() > self.max_cached_len {
+            self.stats.uncached.set(self.stats.uncached.get() + 1);
+            return self.uncached_idx(string);
+        }
+        let pushed = commands.pushed();
+        if pushed != self.pending_for {
+            self.pending.clear();
+            self.pending_for = pushed;
+        }
+        let idx = match self.lru.get(string) {
+            Some(idx) => {
+                self.stats.hits.set(self.stats.hits.get() + 1);
+                *idx
+            }
+            None => {
+                self.stats.misses.set(self.stats.misses.get() + 1);
+                // If we've reached capacity, evict the least-used entry from
+                // bridge string cache, and insert new string there. Otherwise,
+                // place the string at the next valid index
+                let idx = if self.lru.is_full() {
+                    let value_to_insert_at = *self.lru.back().unwrap().1;
+                    // Pending entries are the most recently used, so if the least recently used
+                    // one is pending, they all are, and the string is sent uncached instead
+                    if self.pending.contains(&value_to_insert_at) {
+                        return self.uncached_idx(string);
+                    }
+                    // Buffered commands may refer to the evicted string, so they must be applied first.
+                    // Uncached strings are kept, as the caller may not have buffered its command yet.
+                    if self.batched[value_to_insert_at as usize] {
+                        commands.flush();
+                        self.batched.fill(false);
+                    }
+                    value_to_insert_at
+                } else {
+                    self.lru.len() as u32 + 1
+                };
+                self.lru.put(string.to_owned(), idx);
+                self.send(string, idx);
+                idx
+            }
+        };
+        self.batched[idx as usize] = true;
+        self.pending.push(idx);
+        idx
+    }
+
+    /// Sends `string` at an index past the LRU cache, released on the next flush.
+    fn uncached_idx(&mut self, string: &str) -> u32 {
+        let idx = self.free.pop().unwrap_or_else(|| self.next_idx());
+        self.uncached.push(idx);
+        self.send(string, idx);
+        idx
+    }
+
+    /// Returns the index of the pinned string `string`, sending it to JS on first use.
+    pub(crate) fn pinned_idx(&mut self, string: &'static str) -> u32 {
+        if string.is_empty() {
+            return 0;
+        }
+        if let Some(idx) = self.pinned.get(string) {
+            self.stats.hits.set(self.stats.hits.get() + 1);
+            return *idx;
+        }
+        self.stats.misses.set(self.stats.misses.get() + 1);
+        let idx = self.free.pop().unwrap_or_else(|| self.next_idx());
+        self.pinned.insert(string, idx);
+        self.send(string, idx);
+        idx
+    }
+
+    /// Flushes `commands`, then releases the strings only they referred to.
+    pub(crate) fn flush(&mut self, commands: &CommandBuffer) {
+        for idx in &self.uncached {
+            commands.release_string(*idx);
+        }
+        commands.flush();
+        self.free.append(&mut self.uncached);
+        self.batched.fill(false);
+    }
+
+    fn next_idx(&mut self) -> u32 {
+        self.next_idx += 1;
+        self.next_idx - 1
+    }
+
+    fn send(&mut self, string: &str, idx: u32) {
+        self.encoded.clear();
+        self.encoded.extend(string.encode_utf16());
+        #[cfg(not(test))]
+        bridge::intern_string_at(&self.encoded, idx);
+        #[cfg(test)]
+        self.sent.push((string.to_owned(), idx));
+    }
+}
+
+/// Counts of the lookups made in a renderer's string cache, passed in [MountOptions](crate::MountOptions).
+///
+/// Every string set on the DOM, like text and attribute values, is looked up in the cache, and sent
+/// to JS on a miss. A low [hit rate](StringCacheStats::hit_rate) suggests raising
+/// [string_cache_capacity](crate::MountOptions::string_cache_capacity).
+#[derive(Clone, Default)]
+pub struct StringCacheStats {
+    hits: Rc<Cell<u64>>,
+    misses: Rc<Cell<u64>>,
+    uncached: Rc<Cell<u64>>,
+}
+
+impl StringCacheStats {
+    /// Creates a new set of counts, all zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of strings found in the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits.get()
+    }
+
+    /// The number of strings not found in the cache, and sent to JS.
+    pub fn misses(&self) -> u64 {
+        self.misses.get()
+    }
+
+    /// The number of strings too long to be cached, and sent to JS.
+    pub fn uncached(&self) -> u64 {
+        self.uncached.get()
+    }
+
+    /// The fraction of cacheable strings found in the cache, or `0.0` if none have been looked up.
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits() + self.misses();
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits() as f64 / lookups as f64
+        }
+    }
+
+    /// Sets all counts back to zero.
+    pub fn reset(&self) {
+        self.hits.set(0);
+        self.misses.set(0);
+        self.uncached.set(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sent(cache: &mut StringCache) -> Vec<(String, u32)> {
+        std::mem::take(&mut cache.sent)
+    }
+
+    /// Looks up `string` for a command of its own, as if that command were pushed right after.
+    fn command_idx(cache: &mut StringCache, string: &str, commands: &CommandBuffer) -> u32 {
+        let idx = cache.idx(string, commands);
+        cache.pending.clear();
+        idx
+    }
+
+    #[test]
+    fn lru_eviction() {
+        let commands = CommandBuffer::new();
+        let stats = StringCacheStats::new();
+        let mut cache = StringCache::new(2, 16, stats.clone());
+
+        assert_eq!(command_idx(&mut cache, "a", &commands), 1);
+        assert_eq!(command_idx(&mut cache, "b", &commands), 2);
+        assert_eq!(command_idx(&mut cache, "a", &commands), 1);
+        assert_eq!(sent(&mut cache), [("a".to_owned(), 1), ("b".to_owned(), 2)]);
+
+        // "b" is the least recently used, so it is evicted
+        assert_eq!(command_idx(&mut cache, "c", &commands), 2);
+        assert_eq!(command_idx(&mut cache, "b", &commands), 1);
+        assert_eq!(sent(&mut cache), [("c".to_owned(), 2), ("b".to_owned(), 1)]);
+
+        assert_eq!((stats.hits(), stats.misses(), stats.uncached()), (1, 4, 0));
+        assert_eq!(stats.hit_rate(), 0.2);
+    }
+
+    #[test]
+    fn eviction_of_batched_string_flushes() {
+        let commands = CommandBuffer::new();
+        let mut cache = StringCache::new(2, 16, StringCacheStats::new());
+
+        command_idx(&mut cache, "a", &commands);
+        cache.flush(&commands);
+        command_idx(&mut cache, "b", &commands);
+        assert_eq!(cache.batched, [false, false, true]);
+
+        // "a" is no longer referred to by buffered commands, so it is evicted without a flush
+        command_idx(&mut cache, "c", &commands);
+        assert_eq!(cache.batched, [false, true, true]);
+
+        // "b" may be referred to by buffered commands, so they are flushed before it is evicted
+        command_idx(&mut cache, "d", &commands);
+        assert_eq!(cache.batched, [false, false, true]);
+        assert_eq!(command_idx(&mut cache, "c", &commands), 1);
+    }
+
+    #[test]
+    fn pending_strings_are_not_evicted() {
+        let commands = CommandBuffer::new();
+        let stats = StringCacheStats::new();
+        let mut cache = StringCache::new(1, 16, stats.clone());
+
+        // a command referring to several strings looks them all up before it is pushed,
+        // so the cache sends those it has no room for uncached instead of evicting the others
+        assert_eq!(cache.idx("color", &commands), 1);
+        assert_eq!(cache.idx("red", &commands), 2);
+        assert_eq!(cache.idx("color", &commands), 1);
+        assert_eq!(cache.idx("blue", &commands), 3);
+        assert_eq!(
+            sent(&mut cache),
+            [
+                ("color".to_owned(), 1),
+                ("red".to_owned(), 2),
+                ("blue".to_owned(), 3)
+            ]
+        );
+        assert_eq!(cache.uncached, [2, 3]);
+        assert_eq!((stats.hits(), stats.misses(), stats.uncached()), (1, 3, 0));
+
+        // once the command is pushed, the strings it referred to may be evicted
+        commands.set_style_property(0, 1, 3);
+        assert_eq!(cache.idx("color", &commands), 1);
+        assert_eq!(cache.pending, [1]);
+    }
+
+    #[test]
+    fn pinned_strings() {
+        let commands = CommandBuffer::new();
+        let stats = StringCacheStats::new();
+        let mut cache = StringCache::new(1, 16, stats.clone());
+
+        assert_eq!(cache.pinned_idx("div"), 2);
+        assert_eq!(cache.pinned_idx("class"), 3);
+        assert_eq!(command_idx(&mut cache, "a", &commands), 1);
+        assert_eq!(command_idx(&mut cache, "b", &commands), 1);
+        // pinned strings are never evicted, and are separate from cached copies of them
+        assert_eq!(cache.pinned_idx("div"), 2);
+        assert_eq!(command_idx(&mut cache, "div", &commands), 1);
+        assert_eq!(
+            sent(&mut cache),
+            [
+                ("div".to_owned(), 2),
+                ("class".to_owned(), 3),
+                ("a".to_owned(), 1),
+                ("b".to_owned(), 1),
+                ("div".to_owned(), 1)
+            ]
+        );
+        assert_eq!((stats.hits(), stats.misses()), (1, 5));
+    }
+
+    #[test]
+    fn max_cached_len() {
+        let commands = CommandBuffer::new();
+        let stats = StringCacheStats::new();
+        let mut cache = StringCache::new(2, 4, stats.clone());
+
+        assert_eq!(command_idx(&mut cache, "abcd", &commands), 1);
+        assert_eq!(command_idx(&mut cache, "abcd", &commands), 1);
+        // longer strings are sent every time, past the LRU cache's indices
+        assert_eq!(command_idx(&mut cache, "abcde", &commands), 3);
+        assert_eq!(command_idx(&mut cache, "abcde", &commands), 4);
+        assert_eq!(cache.pinned_idx("span"), 5);
+        assert_eq!(
+            sent(&mut cache),
+            [
+                ("abcd".to_owned(), 1),
+                ("abcde".to_owned(), 3),
+                ("abcde".to_owned(), 4),
+                ("span".to_owned(), 5)
+            ]
+        );
+        assert_eq!((stats.hits(), stats.misses(), stats.uncached()), (1, 2, 2));
+    }
+
+    #[test]
+    fn empty_string() {
+        let commands = CommandBuffer::new();
+        let stats = StringCacheStats::new();
+        let mut cache = StringCache::new(1, 16, stats.clone());
+
+        assert_eq!(command_idx(&mut cache, "", &commands), 0);
+        assert_eq!(cache.pinned_idx(""), 0);
+        assert!(sent(&mut cache).is_empty());
+        assert_eq!(stats.hit_rate(), 0.0);
+    }
+}
@@ -0,0 +1,383 @@
+//! A typed builder for inline styles, passed to an element's `styles` parameter.
+//!
+//! Unlike the `style` attribute, which is replaced as a whole whenever it changes, `styles` are diffed
+//! property by property, so only the properties that changed are set on the element:
+//!
+//! ```rust
+//! use avalanche::{component, tracked, state, View};
+//! use avalanche_web::components::Div;
+//! use avalanche_web::style::{px, Display, Style};
+//!
+//! #[component]
+//! fn Slider() -> View {
+//!     let (offset, _) = state(self, || 0.0);
+//!     Div(
+//!         self,
+//!         styles = Style::new()
+//!             .display(Display::Flex)
+//!             .width(px(200))
+//!             .transform(format!("translateX({}px)", tracked!(offset)))
+//!             .var("--accent", "tomato"),
+//!         []
+//!     )
+//! }
+//! ```
+//!
+//! Setting both the `style` attribute and `styles` on the same element is not supported.
+
+use std::borrow::Cow;
+use std::fmt;
+
+/// A list of CSS properties and their values, applied to an element through its `styles` parameter.
+///
+/// Properties without a dedicated method can be set with [property](Style::property), and CSS custom
+/// properties with [var](Style::var). If a property is set more than once, the last value is used.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Style<'a> {
+    pub(crate) properties: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+}
+
+macro_rules! style_properties {
+    ($($(#[$attr:meta])* $name:literal => $ident:ident : $ty:ty),* $(,)?) => {
+        $(
+            $(#[$attr])*
+            pub fn $ident(self, value: impl Into<$ty>) -> Self {
+                self.property($name, value.into().to_string())
+            }
+        )*
+    };
+}
+
+macro_rules! str_style_properties {
+    ($($(#[$attr:meta])* $name:literal => $ident:ident),* $(,)?) => {
+        $(
+            $(#[$attr])*
+            pub fn $ident(self, value: impl Into<Cow<'a, str>>) -> Self {
+                self.property($name, value)
+            }
+        )*
+    };
+}
+
+impl<'a> Style<'a> {
+    /// Creates a style with no properties.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the CSS property `name` to `value`.
+    pub fn property(
+        mut self,
+        name: impl Into<Cow<'a, str>>,
+        value: impl Into<Cow<'a, str>>,
+    ) -> Self {
+        self.properties.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the CSS custom property `name` to `value`. A leading `--` is added to `name` if missing.
+    pub fn var(self, name: impl Into<Cow<'a, str>>, value: impl Into<Cow<'a, str>>) -> Self {
+        let name = name.into();
+        let name = if name.starts_with("--") {
+            name
+        } else {
+            Cow::Owned(format!("--{}", name))
+        };
+        self.property(name, value)
+    }
+
+    style_properties! {
+        /// Sets the `width` property.
+        "width" => width: Length,
+        /// Sets the `height` property.
+        "height" => height: Length,
+        /// Sets the `min-width` property.
+        "min-width" => min_width: Length,
+        /// Sets the `min-height` property.
+        "min-height" => min_height: Length,
+        /// Sets the `max-width` property.
+        "max-width" => max_width: Length,
+        /// Sets the `max-height` property.
+        "max-height" => max_height: Length,
+        /// Sets the `top` property.
+        "top" => top: Length,
+        /// Sets the `right` property.
+        "right" => right: Length,
+        /// Sets the `bottom` property.
+        "bottom" => bottom: Length,
+        /// Sets the `left` property.
+        "left" => left: Length,
+        /// Sets the `margin` property.
+        "margin" => margin: Length,
+        /// Sets the `margin-top` property.
+        "margin-top" => margin_top: Length,
+        /// Sets the `margin-right` property.
+        "margin-right" => margin_right: Length,
+        /// Sets the `margin-bottom` property.
+        "margin-bottom" => margin_bottom: Length,
+        /// Sets the `margin-left` property.
+        "margin-left" => margin_left: Length,
+        /// Sets the `padding` property.
+        "padding" => padding: Length,
+        /// Sets the `padding-top` property.
+        "padding-top" => padding_top: Length,
+        /// Sets the `padding-right` property.
+        "padding-right" => padding_right: Length,
+        /// Sets the `padding-bottom` property.
+        "padding-bottom" => padding_bottom: Length,
+        /// Sets the `padding-left` property.
+        "padding-left" => padding_left: Length,
+        /// Sets the `gap` property.
+        "gap" => gap: Length,
+        /// Sets the `font-size` property.
+        "font-size" => font_size: Length,
+        /// Sets the `line-height` property.
+        "line-height" => line_height: Length,
+        /// Sets the `border-radius` property.
+        "border-radius" => border_radius: Length,
+        /// Sets the `display` property.
+        "display" => display: Display,
+        /// Sets the `position` property.
+        "position" => position: Position,
+        /// Sets the `overflow` property.
+        "overflow" => overflow: Overflow,
+        /// Sets the `visibility` property.
+        "visibility" => visibility: Visibility,
+        /// Sets the `flex-direction` property.
+        "flex-direction" => flex_direction: FlexDirection,
+        /// Sets the `opacity` property.
+        "opacity" => opacity: f64,
+        /// Sets the `z-index` property.
+        "z-index" => z_index: i32,
+        /// Sets the `flex-grow` property.
+        "flex-grow" => flex_grow: f64,
+        /// Sets the `flex-shrink` property.
+        "flex-shrink" => flex_shrink: f64,
+    }
+
+    str_style_properties! {
+        /// Sets the `color` property.
+        "color" => color,
+        /// Sets the `background` property.
+        "background" => background,
+        /// Sets the `background-color` property.
+        "background-color" => background_color,
+        /// Sets the `border` property.
+        "border" => border,
+        /// Sets the `box-shadow` property.
+        "box-shadow" => box_shadow,
+        /// Sets the `cursor` property.
+        "cursor" => cursor,
+        /// Sets the `font-family` property.
+        "font-family" => font_family,
+        /// Sets the `font-weight` property.
+        "font-weight" => font_weight,
+        /// Sets the `transform` property.
+        "transform" => transform,
+        /// Sets the `transition` property.
+        "transition" => transition,
+        /// Sets the `align-items` property.
+        "align-items" => align_items,
+        /// Sets the `justify-content` property.
+        "justify-content" => justify_content,
+        /// Sets the `flex` property.
+        "flex" => flex,
+        /// Sets the `grid-template-columns` property.
+        "grid-template-columns" => grid_template_columns,
+        /// Sets the `grid-template-rows` property.
+        "grid-template-rows" => grid_template_rows,
+    }
+}
+
+/// A CSS length, created with functions like [px] and [percent].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Length {
+    /// A length in pixels.
+    Px(f64),
+    /// A percentage of the containing block.
+    Percent(f64),
+    /// A multiple of the element's font size.
+    Em(f64),
+    /// A multiple of the root element's font size.
+    Rem(f64),
+    /// A percentage of the viewport's width.
+    Vw(f64),
+    /// A percentage of the viewport's height.
+    Vh(f64),
+    /// The `auto` keyword, leaving the length to the browser.
+    Auto,
+}
+
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Length::Px(n) => write!(f, "{}px", n),
+            Length::Percent(n) => write!(f, "{}%", n),
+            Length::Em(n) => write!(f, "{}em", n),
+            Length::Rem(n) => write!(f, "{}rem", n),
+            Length::Vw(n) => write!(f, "{}vw", n),
+            Length::Vh(n) => write!(f, "{}vh", n),
+            Length::Auto => f.write_str("auto"),
+        }
+    }
+}
+
+/// A length in pixels.
+pub fn px(n: impl Into<f64>) -> Length {
+    Length::Px(n.into())
+}
+
+/// A length as a percentage of the containing block.
+pub fn percent(n: impl Into<f64>) -> Length {
+    Length::Percent(n.into())
+}
+
+/// A length relative to the element's font size.
+pub fn em(n: impl Into<f64>) -> Length {
+    Length::Em(n.into())
+}
+
+/// A length relative to the root element's font size.
+pub fn rem(n: impl Into<f64>) -> Length {
+    Length::Rem(n.into())
+}
+
+/// A length as a percentage of the viewport's width.
+pub fn vw(n: impl Into<f64>) -> Length {
+    Length::Vw(n.into())
+}
+
+/// A length as a percentage of the viewport's height.
+pub fn vh(n: impl Into<f64>) -> Length {
+    Length::Vh(n.into())
+}
+
+macro_rules! keyword_enum {
+    ($(#[$attr:meta])* $name:ident { $($variant:ident => $value:literal),* $(,)? }) => {
+        $(#[$attr])*
+        #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+        #[non_exhaustive]
+        pub enum $name {
+            $($variant),*
+        }
+
+        impl $name {
+            fn as_str(self) -> &'static str {
+                match self {
+                    $($name::$variant => $value),*
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+    };
+}
+
+keyword_enum! {
+    /// Values of the `display` property.
+    Display {
+        None => "none",
+        Block => "block",
+        Inline => "inline",
+        InlineBlock => "inline-block",
+        Flex => "flex",
+        InlineFlex => "inline-flex",
+        Grid => "grid",
+        InlineGrid => "inline-grid",
+        Contents => "contents",
+    }
+}
+
+keyword_enum! {
+    /// Values of the `position` property.
+    Position {
+        Static => "static",
+        Relative => "relative",
+        Absolute => "absolute",
+        Fixed => "fixed",
+        Sticky => "sticky",
+    }
+}
+
+keyword_enum! {
+    /// Values of the `overflow` property.
+    Overflow {
+        Visible => "visible",
+        Hidden => "hidden",
+        Clip => "clip",
+        Scroll => "scroll",
+        Auto => "auto",
+    }
+}
+
+keyword_enum! {
+    /// Values of the `visibility` property.
+    Visibility {
+        Visible => "visible",
+        Hidden => "hidden",
+        Collapse => "collapse",
+    }
+}
+
+keyword_enum! {
+    /// Values of the `flex-direction` property.
+    FlexDirection {
+        Row => "row",
+        RowReverse => "row-reverse",
+        Column => "column",
+        ColumnReverse => "column-reverse",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn properties<'a>(style: &'a Style) -> Vec<(&'a str, &'a str)> {
+        style
+            .properties
+            .iter()
+            .map(|(name, value)| (&**name, &**value))
+            .collect()
+    }
+
+    #[test]
+    fn var_names() {
+        let style = Style::new()
+            .var("accent", "tomato")
+            .var("--gap", "4px")
+            .var(String::from("-x"), "1");
+        assert_eq!(
+            properties(&style),
+            [("--accent", "tomato"), ("--gap", "4px"), ("---x", "1")]
+        );
+    }
+
+    #[test]
+    fn var_borrows_prefixed_names() {
+        let style = Style::new().var("--accent", "tomato");
+        assert!(matches!(style.properties[0].0, Cow::Borrowed("--accent")));
+    }
+
+    #[test]
+    fn typed_properties() {
+        let style = Style::new()
+            .width(px(200))
+            .height(percent(50.5))
+            .display(Display::Flex)
+            .opacity(0.5);
+        assert_eq!(
+            properties(&style),
+            [
+                ("width", "200px"),
+                ("height", "50.5%"),
+                ("display", "flex"),
+                ("opacity", "0.5")
+            ]
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use avalanche::renderer::Priority;
+use avalanche::{component, keyed, state, tracked, View};
+use wasm_bindgen::closure::Closure;
+use web_sys::HtmlDivElement;
+
+use crate::bridge;
+use crate::components::Div;
+use crate::events::ListenerOptions;
+use crate::style::{px, Style};
+use crate::{element_size, node_ref};
+
+/// The row height assumed until rows are first measured, if no row height is given.
+const ESTIMATED_ROW_HEIGHT: f64 = 24.0;
+
+/// The smallest row height used, as the number of visible rows and the first visible row are
+/// found by dividing by the row height.
+const MIN_ROW_HEIGHT: f64 = 1.0;
+
+/// Returns the height of each row: `fixed` if given, and otherwise the average height of the rows
+/// rendered when they were last measured, but no less than [MIN_ROW_HEIGHT].
+fn current_row_height(fixed: Option<f64>, rows_height: f64, rows_measured: usize) -> f64 {
+    let height = match fixed {
+        Some(height) => height,
+        None if rows_height > 0.0 && rows_measured > 0 => rows_height / rows_measured as f64,
+        None => ESTIMATED_ROW_HEIGHT,
+    };
+    // also replaces NaN
+    height.max(MIN_ROW_HEIGHT)
+}
+
+/// Returns the start and end of the rows to render: those within the viewport,
+/// plus `overscan` rows past each edge.
+fn rendered_rows(
+    len: usize,
+    overscan: usize,
+    first_visible: usize,
+    row_height: f64,
+    viewport_height: f64,
+) -> (usize, usize) {
+    let visible = (viewport_height / row_height).ceil() as usize + 1;
+    let start = first_visible.min(len).saturating_sub(overscan);
+    let end = first_visible
+        .saturating_add(visible)
+        .saturating_add(overscan)
+        .min(len);
+    (start, end)
+}
+
+/// A scrolling list rendering only the rows within and near its viewport.
+///
+/// Rows are rendered by calling `row` with their index, and are keyed by index, or by `key` if given,
+/// so rows staying in view while scrolling keep their state. Rows scrolled far enough out of view are
+/// removed along with their state. Spacer elements above and below the rendered rows keep the
+/// scroll height equal to that of the whole list.
+///
+/// The list scrolls within its own element, which must be given a height, typically through `class`.
+/// Every row is assumed to have the same height: `row_height` if given, and otherwise the height of
+/// the rendered rows, measured once they are rendered.
+///
+/// # Example
+/// ```rust
+/// use avalanche::{component, tracked, View};
+/// use avalanche_web::components::{Div, Text};
+/// use avalanche_web::VirtualList;
+///
+/// #[component]
+/// fn Log(lines: &[String]) -> View {
+///     VirtualList(
+///         self,
+///         len = tracked!(lines).len(),
+///         row_height = 20.0,
+///         class = "log",
+///         &|i| Div(self, Text(self, &tracked!(lines)[i]))
+///     )
+/// }
+/// ```
+#[component]
+pub fn VirtualList(
+    /// The number of rows.
+    len: usize,
+    /// The height of every row in CSS pixels. If not given, it is measured from the rendered rows.
+    /// Heights below one pixel are treated as one pixel.
+    #[optional]
+    row_height: f64,
+    /// The number of rows rendered past each edge of the viewport.
+    #[default = 3]
+    overscan: usize,
+    /// The class of the scrolling element.
+    #[optional]
+    class: &str,
+    /// Returns the key of the row at an index. Rows are keyed by index if not given.
+    #[optional]
+    key: &dyn Fn(usize) -> String,
+    /// Renders the row at an index.
+    row: &dyn Fn(usize) -> View,
+) -> View {
+    let viewport = node_ref::<HtmlDivElement>(self);
+    let rows_ref = node_ref::<HtmlDivElement>(self);
+    let viewport_size = element_size(self, &viewport);
+    let rows_size = element_size(self, &rows_ref);
+    let (first_visible, set_first_visible) = state(self, || 0usize);
+    // the range of rows in the document, which the latest rows size is for
+    let (measured_range, _) = state(self, || Rc::new(Cell::new((0usize, 0usize))));
+
+    let height = current_row_height(
+        tracked!(row_height),
+        tracked!(rows_size).height,
+        tracked!(measured_range).get().1 - tracked!(measured_range).get().0,
+    );
+    let range = rendered_rows(
+        tracked!(len),
+        tracked!(overscan),
+        *tracked!(first_visible),
+        tracked!(height),
+        tracked!(viewport_size).height,
+    );
+    // the rows are only in the document once rendering completes, so the range is recorded after
+    let rendered = tracked!(range);
+    let measured = tracked!(measured_range).clone();
+    bridge::queue_microtask_once(Closure::once_into_js(move || {
+        tracked!(measured).set(tracked!(rendered))
+    }));
+
+    let rows = (tracked!(range).0..tracked!(range).1).map(|index| match tracked!(key) {
+        Some(key) => keyed(self, key(index), || tracked!(row)(index)),
+        None => keyed(self, index, || tracked!(row)(index)),
+    });
+    let top = tracked!(range).0 as f64 * tracked!(height);
+    let bottom = (tracked!(len) - tracked!(range).1) as f64 * tracked!(height);
+
+    Div(
+        self,
+        node_ref = viewport,
+        class = tracked!(class).unwrap_or_default(),
+        styles = Style::new().property("overflow-y", "auto"),
+        on_scroll_with = (ListenerOptions::PASSIVE, move |e| {
+            if let Some(viewport) = e.current_target() {
+                let first = (viewport.scroll_top() as f64 / tracked!(height)) as usize;
+                if tracked!(first) != *tracked!(first_visible) {
+                    set_first_visible.update_with_priority(Priority::Animation, move |visible| {
+                        *visible = tracked!(first)
+                    });
+                }
+            }
+        }),
+        [
+            Div(self, styles = Style::new().height(px(tracked!(top)))),
+            Div(self, node_ref = rows_ref, tracked!(rows)),
+            Div(self, styles = Style::new().height(px(tracked!(bottom)))),
+        ]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn row_heights() {
+        assert_eq!(current_row_height(Some(30.0), 100.0, 4), 30.0);
+        assert_eq!(current_row_height(None, 0.0, 0), ESTIMATED_ROW_HEIGHT);
+        assert_eq!(current_row_height(None, 0.0, 4), ESTIMATED_ROW_HEIGHT);
+        // rows of varying heights are averaged
+        assert_eq!(current_row_height(None, 20.0 + 30.0 + 40.0, 3), 30.0);
+    }
+
+    #[test]
+    fn zero_row_height() {
+        // row heights are clamped, so the visible rows can be found by dividing by them
+        assert_eq!(current_row_height(Some(0.0), 0.0, 0), MIN_ROW_HEIGHT);
+        assert_eq!(current_row_height(Some(-5.0), 0.0, 0), MIN_ROW_HEIGHT);
+        assert_eq!(current_row_height(Some(f64::NAN), 0.0, 0), MIN_ROW_HEIGHT);
+        assert_eq!(current_row_height(None, 0.5, 2), MIN_ROW_HEIGHT);
+        let height = current_row_height(Some(0.0), 0.0, 0);
+        assert_eq!(rendered_rows(100, 2, 10, height, 50.0), (8, 63));
+    }
+
+    #[test]
+    fn visible_rows_and_overscan() {
+        // 5 rows fit in the viewport, and a partially visible one may follow them
+        assert_eq!(rendered_rows(100, 0, 10, 20.0, 100.0), (10, 16));
+        assert_eq!(rendered_rows(100, 3, 10, 20.0, 100.0), (7, 19));
+        assert_eq!(rendered_rows(100, 3, 0, 20.0, 90.0), (0, 9));
+        // overscan is clamped to the start and end of the list
+        assert_eq!(rendered_rows(100, 3, 1, 20.0, 100.0), (0, 10));
+        assert_eq!(rendered_rows(100, 3, 95, 20.0, 100.0), (92, 100));
+    }
+
+    #[test]
+    fn empty_and_short_lists() {
+        assert_eq!(rendered_rows(0, 3, 0, 20.0, 100.0), (0, 0));
+        assert_eq!(rendered_rows(4, 3, 0, 20.0, 100.0), (0, 4));
+        assert_eq!(rendered_rows(100, 3, 0, 20.0, 0.0), (0, 4));
+    }
+
+    #[test]
+    fn scrolled_past_the_end() {
+        // as after the list shrinks while scrolled to its end
+        assert_eq!(rendered_rows(10, 3, 50, 20.0, 100.0), (7, 10));
+        assert_eq!(rendered_rows(10, 0, 50, 20.0, 100.0), (10, 10));
+        assert_eq!(rendered_rows(10, 3, usize::MAX, 20.0, 100.0), (7, 10));
+    }
+}
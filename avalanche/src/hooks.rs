@@ -1,12 +1,12 @@
 use std::{cell::Cell, marker::PhantomData, panic::Location, fmt::{Display, Write}};
 
 use crate::{
-    renderer::{NativeEvent, Scheduler},
+    renderer::{NativeEvent, Priority, Scheduler},
     shared::{Shared, WeakShared},
     tracked::{Gen, InternalGen},
     alloc::{Bump, String as BumpString},
     vdom::{
-        state::{ComponentStateAccess, SharedBox},
+        state::{ComponentStateAccess, SharedBox, StateKey},
         data::{CellBumpVec, ComponentId, VDom}
     },
     ComponentPos, Tracked, View,
@@ -34,6 +34,89 @@ pub struct HookContext<'a, 'bump: 'a> {
     pub(crate) component_pos: ComponentPos<'a>,
     pub(crate) shared: &'a SharedContext<'a, 'bump>,
     pub(crate) bump: &'bump Bump,
+    pub(crate) depth: usize,
+    /// The keys given by `with_key`, each prefixed by its length.
+    pub(crate) scope: Option<&'bump str>,
+}
+
+impl<'a, 'bump> HookContext<'a, 'bump> {
+    /// Returns the depth of the component within the tree of components rendering one another.
+    /// A component rendered within another component's render function is one deeper than it,
+    /// and a mounted root component has depth `1`.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns a context whose hooks identify their state by `key` as well as the location of their call.
+    ///
+    /// Hooks store their state per call site, so calling a hook within a loop or an iterator closure
+    /// makes every iteration share the same state. Passing a context from `with_key`, with a key unique
+    /// to each iteration, gives each iteration its own state, for any hook:
+    ///
+    /// ```rust
+    /// use avalanche::{component, keyed, state, tracked, View};
+    ///
+    /// #[component]
+    /// fn Counters() -> View {
+    ///     let rows = ["a", "b", "c"].iter().map(|name| {
+    ///         let (count, _) = state(self.with_key(name), || 0);
+    ///         keyed(self, name, || Counter(self, count = *tracked!(count)))
+    ///     });
+    ///     ().into()
+    /// }
+    ///
+    /// #[component]
+    /// fn Counter(count: u32) -> View {
+    ///     ().into()
+    /// }
+    /// ```
+    pub fn with_key<K: Display>(self, key: K) -> Self {
+        let mut key_string = BumpString::new_in(self.bump);
+        write!(key_string, "{}", key).expect("a Display implementation returned an error unexpectedly");
+        let mut scope = BumpString::new_in(self.bump);
+        scope.push_str(self.scope.unwrap_or_default());
+        // the length prefix keeps nested keys from being confused with one another
+        write!(scope, "{}:{}", key_string.len(), key_string).unwrap();
+        Self {
+            scope: Some(scope.into_bump_str()),
+            ..self
+        }
+    }
+
+    /// Returns a [Dispatcher] running functions for the component's tree, for hooks
+    /// handling events from outside of it.
+    pub fn dispatcher(&self) -> Dispatcher {
+        Dispatcher {
+            vdom: self.component_pos.vdom.downgrade(),
+            scheduler: self.shared.scheduler.clone(),
+        }
+    }
+}
+
+/// Runs functions handling events from outside of a component tree, like listeners on the window,
+/// outside of the tree's renders, as native events are dispatched to native components.
+#[derive(Clone)]
+pub struct Dispatcher {
+    vdom: WeakShared<VDom>,
+    scheduler: Shared<dyn Scheduler>,
+}
+
+impl Dispatcher {
+    /// Runs `f` within this call, or if the tree is being rendered, as soon as the render completes.
+    /// Does nothing if the tree has been unmounted.
+    pub fn dispatch(&self, f: impl FnOnce() + 'static) {
+        let vdom = match self.vdom.upgrade() {
+            Some(vdom) => vdom,
+            None => return,
+        };
+        if vdom.borrowed() {
+            self.scheduler.exec_mut(|scheduler| {
+                scheduler.schedule_on_ui_thread(Box::new(f), Priority::Immediate)
+            });
+        } else {
+            f();
+        }
+    }
 }
 
 /// Provides a component with component-specific state.
@@ -55,15 +138,14 @@ struct InternalState<T: 'static, S: 'static> {
 }
 
 /// Provides common state storage and access for other state hooks.
-#[track_caller]
 fn internal_state<'a, T: 'static, S: 'static>(
     ctx: HookContext<'a, '_>,
+    state_key: StateKey,
     f: impl FnOnce() -> T,
     setter: S,
-) -> (&'a InternalState<T, S>, Location<'static>) {
-    let location = Location::caller();
+) -> &'a InternalState<T, S> {
     let state_ref = ctx.state.exec_mut(|state| {
-        state.get_or_insert_with(*location, move || {
+        state.get_or_insert_with(state_key, move || {
             SharedBox::new(Box::new(InternalState {
                 val: f(),
                 gen: ctx.gen.gen,
@@ -71,12 +153,20 @@ fn internal_state<'a, T: 'static, S: 'static>(
             }))
         })
     });
-    (
-        state_ref
-            .downcast_ref::<InternalState<T, S>>()
-            .expect("downcast to internal state"),
-        *location,
-    )
+    state_ref
+        .downcast_ref::<InternalState<T, S>>()
+        .expect("downcast to internal state")
+}
+
+/// Creates the key identifying the state of the hook called at the caller's location with `ctx`
+/// and the given key.
+#[track_caller]
+fn state_key(ctx: &HookContext, key: Option<&dyn Display>) -> StateKey {
+    StateKey {
+        location: *Location::caller(),
+        scope: ctx.scope.map(ToOwned::to_owned),
+        key: key.map(ToString::to_string),
+    }
 }
 
 /// A hook that allows a component to keep persistent state across renders.
@@ -91,6 +181,9 @@ fn internal_state<'a, T: 'static, S: 'static>(
 ///
 /// To update the state, use the [set](StateSetter::set) or [update](StateSetter::update) methods on the setter variable.
 ///
+/// If a render of the component does not call the hook, such as when it is behind a condition,
+/// the state is destroyed, and initialized again with `f` on the next call.
+///
 /// # Example
 /// ```rust
 /// use avalanche::{component, tracked, View, state};
@@ -117,15 +210,64 @@ pub fn state<'a, T: 'static>(
     ctx: HookContext<'a, '_>,
     f: fn() -> T,
 ) -> (Tracked<&'a T>, &'a StateSetter<T>) {
-    let location = Location::caller();
+    state_with_key(ctx, state_key(&ctx, None), f)
+}
+
+/// Like [state], but identifies the state by both the location of the call and `key`.
+///
+/// [state] stores its state per call site, so calling it within a loop or an iterator closure
+/// makes every iteration share the same state. `state_keyed` must be used there instead,
+/// with a key unique to each iteration, like the key passed to [keyed]. Alternatively, [state]
+/// may be passed a context from [HookContext::with_key].
+/// As with [state], state whose hook is not called during a render of its component is destroyed.
+///
+/// # Example
+/// ```rust
+/// use avalanche::{component, keyed, state_keyed, tracked, View};
+/// use avalanche_web::components::{Button, Div, Text};
+///
+/// #[component]
+/// fn Counters() -> View {
+///     Div(
+///         self,
+///         ["a", "b", "c"].iter().map(|name| {
+///             let (count, set_count) = state_keyed(self, name, || 0);
+///             keyed(self, name, || {
+///                 Button(
+///                     self,
+///                     on_click = move |_| set_count.update(|count| *count += 1),
+///                     Text(self, format!("{}: {}", name, tracked!(count)))
+///                 )
+///             })
+///         })
+///     )
+/// }
+/// ```
+///
+/// Calling [state] or [store] with `self` within a loop or an iterator closure in a `#[component]` body
+/// is a compile error.
+#[track_caller]
+pub fn state_keyed<'a, T: 'static, K: Display>(
+    ctx: HookContext<'a, '_>,
+    key: K,
+    f: fn() -> T,
+) -> (Tracked<&'a T>, &'a StateSetter<T>) {
+    state_with_key(ctx, state_key(&ctx, Some(&key)), f)
+}
+
+fn state_with_key<'a, T: 'static>(
+    ctx: HookContext<'a, '_>,
+    state_key: StateKey,
+    f: fn() -> T,
+) -> (Tracked<&'a T>, &'a StateSetter<T>) {
     let setter = StateSetter {
         internal_setter: InternalStateSetter::new(
             ctx.component_pos,
             ctx.shared.scheduler.clone(),
-            *location,
+            state_key.clone(),
         ),
     };
-    let (state, _) = internal_state(ctx, f, setter);
+    let state = internal_state(ctx, state_key, f, setter);
     let state_ref = &state.val;
     let tracked_state_ref = Tracked::new(state_ref, state.gen.into());
 
@@ -137,7 +279,7 @@ struct InternalStateSetter<T: 'static, S: 'static> {
     vdom: WeakShared<VDom>,
     component_id: ComponentId,
     scheduler: Shared<dyn Scheduler>,
-    location: Location<'static>,
+    state_key: StateKey,
     phantom: PhantomData<(T, S)>,
 }
 
@@ -147,7 +289,7 @@ impl<T: 'static, S: 'static> Clone for InternalStateSetter<T, S> {
             vdom: self.vdom.clone(),
             component_id: self.component_id,
             scheduler: self.scheduler.clone(),
-            location: self.location,
+            state_key: self.state_key.clone(),
             phantom: PhantomData,
         }
     }
@@ -157,19 +299,20 @@ impl<T: 'static, S: 'static> InternalStateSetter<T, S> {
     fn new(
         component_pos: ComponentPos,
         scheduler: Shared<dyn Scheduler>,
-        location: Location<'static>,
+        state_key: StateKey,
     ) -> Self {
         Self {
             vdom: component_pos.vdom.downgrade(),
             component_id: component_pos.component_id,
             scheduler,
-            location,
+            state_key,
             phantom: PhantomData,
         }
     }
 
-    /// Same as `update`, but also provides the `Gen` the root is on before the state update completes
-    fn update_with_gen<F: FnOnce(&mut T, Gen) + 'static>(&self, f: F) {
+    /// Same as `update`, but also provides the `Gen` the root is on before the state update completes,
+    /// and schedules the update with the given priority
+    fn update_with_gen<F: FnOnce(&mut T, Gen) + 'static>(&self, f: F, priority: Priority) {
         let vdom_clone = match self.vdom.upgrade() {
             Some(vdom) => vdom,
             None => {
@@ -179,7 +322,7 @@ impl<T: 'static, S: 'static> InternalStateSetter<T, S> {
         };
         let vdom_clone_2 = vdom_clone.clone();
         let scheduler_clone = self.scheduler.clone();
-        let location_copy = self.location;
+        let state_key = self.state_key.clone();
         let component_id_copy = self.component_id;
 
         self.scheduler.exec_mut(move |scheduler| {
@@ -189,15 +332,21 @@ impl<T: 'static, S: 'static> InternalStateSetter<T, S> {
                     let vnode = match vdom.children.get_mut(&component_id_copy) {
                         Some(vnode) => vnode,
                         None => {
-                            // TODO: emit warning
+                            vdom.renderer
+                                .log("avalanche: ignored a state update for a removed component");
+                            return;
+                        }
+                    };
+                    let entry = match vnode.state.get_mut(&state_key) {
+                        Some(entry) => entry,
+                        None => {
+                            // the state was destroyed after its hook was no longer called
+                            vdom.renderer
+                                .log("avalanche: ignored a state update for destroyed state");
                             return;
                         }
                     };
-                    let shared_box = vnode
-                        .state
-                        .get_mut(&location_copy)
-                        .expect("state at hook location");
-                    let any_mut = shared_box.get_mut();
+                    let any_mut = entry.value.get_mut();
                     let state = any_mut
                         .downcast_mut::<InternalState<T, S>>()
                         .expect("state with setter's type");
@@ -207,13 +356,13 @@ impl<T: 'static, S: 'static> InternalStateSetter<T, S> {
                     vdom.mark_node_dirty(component_id_copy);
                     (vdom.update_vdom)(vdom, &vdom_clone_2, &scheduler_clone, None);
                 })
-            }));
+            }), priority);
         });
     }
 
     /// Internal implementation of `StateSetter`'s set.
     pub fn set(&self, val: T) {
-        self.update_with_gen(move |state, _| *state = val);
+        self.update_with_gen(move |state, _| *state = val, Priority::default());
     }
 }
 
@@ -228,11 +377,21 @@ impl<T> StateSetter<T> {
     ///
     /// The update is not performed immediately; its effect will only be accessible
     /// on its component's rerender. Note that `update` always triggers a rerender, and the state value
-    /// is marked as updated, even if the given function performs no mutations.
+    /// is marked as updated, even if the given function performs no mutations. If the component
+    /// has been removed, or the state destroyed, by the time the update runs, it is ignored.
     #[inline]
     pub fn update<F: FnOnce(&mut T) + 'static>(&self, f: F) {
-        self.internal_setter.update_with_gen(|val, _| f(val))
+        self.internal_setter.update_with_gen(|val, _| f(val), Priority::default())
     }
+
+    /// Like `update`, which uses [Priority::Normal], but schedules the rerender with the given `priority`.
+    /// For instance, updates driving an animation may use [Priority::Animation] to rerender once per frame,
+    /// and updates that must be shown before pending events are handled may use [Priority::Immediate].
+    #[inline]
+    pub fn update_with_priority<F: FnOnce(&mut T) + 'static>(&self, priority: Priority, f: F) {
+        self.internal_setter.update_with_gen(|val, _| f(val), priority)
+    }
+
     ///
     /// Sets the state to the given value.
     ///
@@ -291,15 +450,34 @@ impl<T> Clone for StateSetter<T> {
 pub fn store<'a, T: 'static>(
     ctx: HookContext<'a, '_>,
     f: fn(Gen) -> T,
+) -> (Tracked<&'a T>, &'a StoreSetter<T>) {
+    store_with_key(ctx, state_key(&ctx, None), f)
+}
+
+/// Like [store], but identifies the state by both the location of the call and `key`.
+/// See [state_keyed] for when this is necessary.
+#[track_caller]
+pub fn store_keyed<'a, T: 'static, K: Display>(
+    ctx: HookContext<'a, '_>,
+    key: K,
+    f: fn(Gen) -> T,
+) -> (Tracked<&'a T>, &'a StoreSetter<T>) {
+    store_with_key(ctx, state_key(&ctx, Some(&key)), f)
+}
+
+fn store_with_key<'a, T: 'static>(
+    ctx: HookContext<'a, '_>,
+    state_key: StateKey,
+    f: fn(Gen) -> T,
 ) -> (Tracked<&'a T>, &'a StoreSetter<T>) {
     let setter = StoreSetter {
         setter: InternalStateSetter::new(
             ctx.component_pos,
             ctx.shared.scheduler.clone(),
-            *Location::caller(),
+            state_key.clone(),
         ),
     };
-    let (state, _) = internal_state(ctx, move || f(ctx.gen), setter);
+    let state = internal_state(ctx, state_key, move || f(ctx.gen), setter);
     let state_ref = &state.val;
     let tracked_state_ref = Tracked::new(state_ref, state.gen.into());
 
@@ -322,7 +500,13 @@ impl<T> StoreSetter<T> {
     /// passing to [Tracked] methods.
     #[inline]
     pub fn update<F: FnOnce(&mut T, Gen) + 'static>(&self, f: F) {
-        self.setter.update_with_gen(f);
+        self.setter.update_with_gen(f, Priority::default());
+    }
+
+    /// Analogous to [StateSetter]'s `update_with_priority` method.
+    #[inline]
+    pub fn update_with_priority<F: FnOnce(&mut T, Gen) + 'static>(&self, priority: Priority, f: F) {
+        self.setter.update_with_gen(f, priority);
     }
 }
 
@@ -23,7 +23,7 @@ use tracked::Gen;
 use vdom::data::{ComponentId, VDom};
 use alloc::Bump;
 
-pub use hooks::{state, store, keyed};
+pub use hooks::{keyed, state, state_keyed, store, store_keyed};
 pub use tracked::Tracked;
 
 /// An attribute macro used to define components.
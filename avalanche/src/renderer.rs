@@ -63,6 +63,11 @@ pub trait Renderer: Downcast {
         len: usize,
     );
 
+    /// Applies any changes the renderer has buffered to the native UI.
+    /// Called once the changes from a render are complete. Renderers that apply changes
+    /// immediately need not implement this method.
+    fn flush(&mut self) {}
+
     /// Logs the given string to a platform-appropriate destination.
     /// This method is a placeholder, and may either be elaborated or replaced with
     /// the `log` crate
@@ -74,7 +79,28 @@ impl_downcast!(Renderer);
 /// An interface to schedule a function on a platform's ui thread.
 pub trait Scheduler {
     /// Schedule the given function to be run on the ui thread in the future.
-    fn schedule_on_ui_thread(&mut self, f: Box<dyn FnOnce()>);
+    /// Schedulers without support for priorities may treat every `priority` alike,
+    /// but must run functions of the same priority in the order they were scheduled.
+    fn schedule_on_ui_thread(&mut self, f: Box<dyn FnOnce()>, priority: Priority);
+}
+
+/// How soon a scheduled function should run.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Priority {
+    /// Run as soon as the currently running code completes, before pending work like input events.
+    Immediate,
+    /// Run after pending work like input events has been handled. State updates use this priority by default.
+    Normal,
+    /// Run before the next frame is drawn, for updates driving animations.
+    Animation,
+    /// Run once the platform is idle, for updates that can be delayed.
+    Idle,
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Priority::Normal
+    }
 }
 
 /// The event data and type of an event dispatched to a native component.
@@ -122,8 +148,11 @@ impl DispatchNativeEvent {
             })
         };
         if vdom.borrowed() {
-            self.scheduler
-                .exec_mut(|scheduler| scheduler.schedule_on_ui_thread(Box::new(exec_event)));
+            // the event would otherwise have been dispatched within this call, so it runs
+            // as soon as the render in progress completes
+            self.scheduler.exec_mut(|scheduler| {
+                scheduler.schedule_on_ui_thread(Box::new(exec_event), Priority::Immediate)
+            });
         } else {
             exec_event();
         }
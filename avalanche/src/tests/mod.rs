@@ -9,9 +9,11 @@ use native_repr::Repr;
 
 use crate::{
     component, keyed,
-    renderer::Scheduler,
+    renderer::{Priority, Scheduler},
     shared::{Shared, WeakShared},
-    state, store, tracked, updated, DefaultComponent, Tracked, View,
+    state, state_keyed, store, tracked,
+    tracked::{Gen, InternalGen, TrackedMap, TrackedVec},
+    tracked_keyed, updated, DefaultComponent, Tracked, View,
 };
 
 use self::{native_mock::Native, renderer::TestRenderer};
@@ -64,7 +66,7 @@ impl TestScheduler {
 }
 
 impl Scheduler for TestScheduler {
-    fn schedule_on_ui_thread(&mut self, f: Box<dyn FnOnce()>) {
+    fn schedule_on_ui_thread(&mut self, f: Box<dyn FnOnce()>, _priority: Priority) {
         self.scheduled_events.exec_mut(|events| events.push_back(f));
     }
 }
@@ -72,6 +74,11 @@ impl Scheduler for TestScheduler {
 /// Render the given component, apply click events to the components with the given names,
 /// then check if the final component tree is equivalent to the expected one.
 pub fn test<C: DefaultComponent>(events: Vec<&str>, expected: Vec<Repr>) {
+    test_at_gen::<C>(InternalGen::new(), events, expected)
+}
+
+/// Like `test`, but starts rendering the tree at the given generation.
+fn test_at_gen<C: DefaultComponent>(gen: InternalGen, events: Vec<&str>, expected: Vec<Repr>) {
     let mut root = Root::new();
     let root_node = root.create_node("root");
     let root = Shared::new(root);
@@ -79,10 +86,11 @@ pub fn test<C: DefaultComponent>(events: Vec<&str>, expected: Vec<Repr>) {
 
     let scheduler = TestScheduler::new(events, root.downgrade());
 
-    let avalanche_root = crate::vdom::Root::new::<_, _, C>(
+    let avalanche_root = crate::vdom::Root::new_at_gen::<_, _, C>(
         Box::new(root_node.clone()),
         renderer,
         scheduler.clone(),
+        gen,
     );
 
     scheduler.run();
@@ -515,3 +523,345 @@ fn nested_keyed() {
         }],
     )
 }
+
+#[component]
+fn TrackedVecChildren() -> View {
+    let (items, update_items) = store(self, |gen| TrackedVec::from_values(["a", "b", "c"], gen));
+
+    Native(
+        self,
+        name = "container",
+        on_click = || {
+            update_items.update(|items, gen| {
+                *items.get_mut(0, gen).unwrap() = "d";
+                items.remove(1, gen);
+                items.push("e", gen);
+                items.swap(1, 2, gen);
+            })
+        },
+        tracked_keyed!(items.iter())
+            .map(|(key, item)| {
+                keyed(self, key, || {
+                    Native(self, name = &format!("item-{}", key), value = tracked!(item))
+                })
+            })
+            .collect(),
+    )
+}
+
+#[test]
+fn tracked_vec_children() {
+    test::<TrackedVecChildren>(
+        vec!["container"],
+        vec![Repr {
+            name: "container".into(),
+            value: String::new(),
+            has_on_click: true,
+            children: vec![
+                Repr {
+                    name: "item-0".into(),
+                    value: "d".into(),
+                    has_on_click: false,
+                    children: vec![],
+                },
+                Repr {
+                    name: "item-3".into(),
+                    value: "e".into(),
+                    has_on_click: false,
+                    children: vec![],
+                },
+                Repr {
+                    name: "item-2".into(),
+                    value: "c".into(),
+                    has_on_click: false,
+                    children: vec![],
+                },
+            ],
+        }],
+    )
+}
+
+#[test]
+fn tracked_collection_gens() {
+    let old_gen: Gen = InternalGen { gen: 1 }.into();
+    let new_gen: Gen = InternalGen { gen: 2 }.into();
+
+    let mut vec = TrackedVec::from_values([1, 2, 3], old_gen);
+    let key = vec.key(1).unwrap();
+    *vec.get_mut(1, new_gen).unwrap() = 4;
+    assert!(vec[0].__avalanche_internal_gen == old_gen);
+    assert!(vec[1].__avalanche_internal_gen == new_gen);
+    assert!(vec.iter().__avalanche_internal_gen == old_gen);
+
+    vec.swap(0, 1, new_gen);
+    assert_eq!(vec.position(key), Some(0));
+    assert!(vec[1].__avalanche_internal_gen == old_gen);
+    assert!(vec.iter().__avalanche_internal_gen == new_gen);
+
+    let mut map = TrackedMap::new(old_gen);
+    map.insert("a", 1, old_gen);
+    map.insert("a", 2, new_gen);
+    assert!(map["a"].__avalanche_internal_gen == new_gen);
+    assert!(map.iter().__avalanche_internal_gen == old_gen);
+    map.retain(|_, _| true, new_gen);
+    assert!(map.iter().__avalanche_internal_gen == old_gen);
+    assert_eq!(map.remove("a", new_gen), Some(2));
+    assert!(map.iter().__avalanche_internal_gen == new_gen);
+}
+
+#[test]
+fn tracked_combinators() {
+    let old_gen: Gen = InternalGen { gen: 1 }.into();
+    let new_gen: Gen = InternalGen { gen: 2 }.into();
+
+    let a = Tracked::new(2, old_gen);
+    let b = Tracked::new(3, new_gen);
+
+    let mapped = a.map(|a| a * 10);
+    assert_eq!(tracked!(mapped), 20);
+    assert!(mapped.__avalanche_internal_gen == old_gen);
+    assert!(a.as_ref().__avalanche_internal_gen == old_gen);
+
+    let zipped = a.zip(b);
+    assert_eq!(tracked!(zipped), (2, 3));
+    assert!(zipped.__avalanche_internal_gen == new_gen);
+
+    let combined = b.combine(a, |b, a| a + b);
+    assert_eq!(tracked!(combined), 5);
+    assert!(combined.__avalanche_internal_gen == new_gen);
+
+    let chained = a.and_then(|a| Tracked::new(a + 1, new_gen));
+    assert_eq!(tracked!(chained), 3);
+    assert!(chained.__avalanche_internal_gen == new_gen);
+    let chained = a.and_then(|a| Tracked::new(a + 1, Gen::escape_hatch_new(false)));
+    assert!(chained.__avalanche_internal_gen == old_gen);
+}
+
+#[component]
+fn GenWrap() -> View {
+    let (count, set_count) = state(self, || 0);
+    let (other, _) = state(self, || 0);
+
+    Native(
+        self,
+        name = "container",
+        vec![
+            Native(
+                self,
+                name = "counter",
+                value = &tracked!(count).to_string(),
+                on_click = move || set_count.update(|count| *count += 1),
+            ),
+            Native(self, name = "static", value = &tracked!(other).to_string()),
+        ],
+    )
+}
+
+#[test]
+fn gen_wrap() {
+    // Start just before the range of a 32-bit generation, so that the clicks
+    // render the tree across that boundary.
+    let gen = InternalGen {
+        gen: u32::MAX as u64 - 2,
+    };
+    test_at_gen::<GenWrap>(
+        gen,
+        vec!["counter"; 6],
+        vec![Repr {
+            name: "container".into(),
+            value: String::new(),
+            has_on_click: false,
+            children: vec![
+                Repr {
+                    name: "counter".into(),
+                    value: "6".into(),
+                    has_on_click: true,
+                    children: vec![],
+                },
+                Repr {
+                    name: "static".into(),
+                    value: "0".into(),
+                    has_on_click: false,
+                    children: vec![],
+                },
+            ],
+        }],
+    )
+}
+
+#[test]
+fn gen_escape_hatch() {
+    let gen = InternalGen {
+        gen: u32::MAX as u64 + 1,
+    };
+    assert!(gen <= Gen::escape_hatch_new(true).gen);
+    assert!(gen > Gen::escape_hatch_new(false).gen);
+
+    let mut gen = InternalGen { gen: u64::MAX - 2 };
+    gen.inc();
+    assert!(gen <= Gen::escape_hatch_new(true).gen);
+}
+
+#[test]
+#[should_panic(expected = "overflowed")]
+fn gen_overflow() {
+    let mut gen = InternalGen { gen: u64::MAX - 1 };
+    gen.inc();
+}
+
+#[component]
+fn KeyedState() -> View {
+    // Items are (state key, node name) pairs, as node names in the mock renderer cannot be reused
+    let (items, set_items) = state(self, || vec![("a", "a1"), ("b", "b1")]);
+
+    Native(
+        self,
+        name = "container",
+        on_click = move || {
+            set_items.update(|items| {
+                if items[0].0 == "a" {
+                    *items = vec![("b", "b1"), ("c", "c1")];
+                } else {
+                    *items = vec![("a", "a2"), ("b", "b1"), ("c", "c1")];
+                }
+            })
+        },
+        tracked!(items)
+            .iter()
+            .map(|(key, name)| {
+                let (count, set_count) = state_keyed(self, key, || 0);
+                keyed(self, name, || {
+                    Native(
+                        self,
+                        name = name,
+                        value = &tracked!(count).to_string(),
+                        on_click = move || set_count.update(|count| *count += 1),
+                    )
+                })
+            })
+            .collect(),
+    )
+}
+
+#[test]
+fn keyed_state() {
+    // State is kept separately for each key, and destroyed when its hook is no longer called
+    test::<KeyedState>(
+        vec!["a1", "b1", "b1", "container", "b1", "container"],
+        vec![Repr {
+            name: "container".into(),
+            value: String::new(),
+            has_on_click: true,
+            children: vec![
+                Repr {
+                    name: "a2".into(),
+                    value: "0".into(),
+                    has_on_click: true,
+                    children: vec![],
+                },
+                Repr {
+                    name: "b1".into(),
+                    value: "3".into(),
+                    has_on_click: true,
+                    children: vec![],
+                },
+                Repr {
+                    name: "c1".into(),
+                    value: "0".into(),
+                    has_on_click: true,
+                    children: vec![],
+                },
+            ],
+        }],
+    )
+}
+
+#[component]
+fn WithKeyState() -> View {
+    // Concatenating each pair of keys would give the same string for both nodes
+    Native(
+        self,
+        name = "container",
+        [("a", "bc", "n1"), ("ab", "c", "n2")]
+            .iter()
+            .map(|(outer, inner, name)| {
+                let (count, set_count) = state(self.with_key(outer).with_key(inner), || 0);
+                keyed(self, name, || {
+                    Native(
+                        self,
+                        name = name,
+                        value = &tracked!(count).to_string(),
+                        on_click = move || set_count.update(|count| *count += 1),
+                    )
+                })
+            })
+            .collect(),
+    )
+}
+
+#[test]
+fn with_key_state() {
+    // State is kept separately for each nested key
+    test::<WithKeyState>(
+        vec!["n1", "n1", "n2"],
+        vec![Repr {
+            name: "container".into(),
+            value: String::new(),
+            has_on_click: false,
+            children: vec![
+                Repr {
+                    name: "n1".into(),
+                    value: "2".into(),
+                    has_on_click: true,
+                    children: vec![],
+                },
+                Repr {
+                    name: "n2".into(),
+                    value: "1".into(),
+                    has_on_click: true,
+                    children: vec![],
+                },
+            ],
+        }],
+    )
+}
+
+fn depth(ctx: crate::hooks::HookContext) -> usize {
+    ctx.depth()
+}
+
+#[component]
+fn Depth() -> View {
+    let depth = depth(self);
+    Native(
+        self,
+        name = "outer",
+        value = &depth.to_string(),
+        vec![DepthChild(self)],
+    )
+}
+
+#[component]
+fn DepthChild() -> View {
+    let depth = depth(self);
+    Native(self, name = "inner", value = &depth.to_string())
+}
+
+#[test]
+fn component_depth() {
+    // Depth follows the components rendering one another, rather than the native tree
+    test::<Depth>(
+        vec![],
+        vec![Repr {
+            name: "outer".into(),
+            value: "1".into(),
+            has_on_click: false,
+            children: vec![Repr {
+                name: "inner".into(),
+                value: "2".into(),
+                has_on_click: false,
+                children: vec![],
+            }],
+        }],
+    )
+}
@@ -1,4 +1,12 @@
-use std::marker::PhantomData;
+use std::{
+    borrow::Borrow,
+    cmp::max,
+    collections::{btree_map, BTreeMap},
+    fmt::{self, Display, Formatter},
+    marker::PhantomData,
+    ops::Index,
+    slice,
+};
 
 #[derive(Copy, Clone)]
 /// A wrapped value that stores whether it has been modified since the last render.
@@ -36,6 +44,63 @@ impl<T> Tracked<T> {
         };
         &mut self.__avalanche_internal_value
     }
+
+    /// Borrows the tracked value, keeping its generation.
+    pub fn as_ref(&self) -> Tracked<&T> {
+        Tracked {
+            __avalanche_internal_value: &self.__avalanche_internal_value,
+            __avalanche_internal_gen: self.__avalanche_internal_gen,
+        }
+    }
+
+    /// Transforms the tracked value with `f`, keeping its generation.
+    ///
+    /// This allows functions outside of `#[component]` bodies to take and return
+    /// [Tracked] values without losing whether they were updated:
+    /// ```rust
+    /// use avalanche::Tracked;
+    ///
+    /// fn format_price(cents: Tracked<u32>) -> Tracked<String> {
+    ///     cents.map(|cents| format!("${}.{:02}", cents / 100, cents % 100))
+    /// }
+    /// ```
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Tracked<U> {
+        Tracked {
+            __avalanche_internal_value: f(self.__avalanche_internal_value),
+            __avalanche_internal_gen: self.__avalanche_internal_gen,
+        }
+    }
+
+    /// Transforms the tracked value with `f`, which itself returns a [Tracked] value.
+    /// The result has the more recent generation of `self` and the returned value.
+    pub fn and_then<U>(self, f: impl FnOnce(T) -> Tracked<U>) -> Tracked<U> {
+        let gen = self.__avalanche_internal_gen;
+        let result = f(self.__avalanche_internal_value);
+        Tracked {
+            __avalanche_internal_value: result.__avalanche_internal_value,
+            __avalanche_internal_gen: max(gen, result.__avalanche_internal_gen),
+        }
+    }
+
+    /// Pairs the tracked value with `other`. The result has the more recent generation of the two.
+    pub fn zip<U>(self, other: Tracked<U>) -> Tracked<(T, U)> {
+        self.combine(other, |a, b| (a, b))
+    }
+
+    /// Combines the tracked value with `other` using `f`. The result has the more recent
+    /// generation of the two.
+    pub fn combine<U, V>(self, other: Tracked<U>, f: impl FnOnce(T, U) -> V) -> Tracked<V> {
+        Tracked {
+            __avalanche_internal_value: f(
+                self.__avalanche_internal_value,
+                other.__avalanche_internal_value,
+            ),
+            __avalanche_internal_gen: max(
+                self.__avalanche_internal_gen,
+                other.__avalanche_internal_gen,
+            ),
+        }
+    }
 }
 
 /// Unwraps and propogates a [Tracked](crate::tracked::Tracked) value.
@@ -48,7 +113,8 @@ impl<T> Tracked<T> {
 /// or `updated` calls with external identifiers as inputs.
 ///
 /// Outside of `#[component]`, provides access to the tracked value
-/// without rewrapping the containing expression.
+/// without rewrapping the containing expression. To transform tracked values there while
+/// keeping their generations, use combinators like [Tracked::map] and [Tracked::combine] instead.
 ///
 /// ## Example
 /// ```rust
@@ -96,10 +162,39 @@ macro_rules! tracked {
 /// mark the value as updated, even if what is at the zeroth index stayed the same.
 ///
 /// However, when we know that the location of a tracked value
-/// is either not important or always static, `keyed_tracked` should be used instead for values coming
+/// is either not important or always static, `tracked_keyed` should be used instead for values coming
 /// from the `store` hook to enable more efficient, fine-grained updates.
 ///
-// TODO: is this the right approach? Also, better documentation + examples
+/// `tracked_keyed` takes an _access path_ made of indexing, field accesses and method calls on a root
+/// value. Any [Tracked] containers along that path, whether they are a bare identifier like `vec` or an explicit
+/// `tracked!(vec)`, are unwrapped without contributing their generations, so the result only depends on the
+/// final [Tracked] value that is accessed. Index and method argument expressions are still tracked as usual,
+/// so in `tracked_keyed!(vec[tracked!(i)])`, a change in `i` marks the result as updated. When the access path
+/// ends in an index or field, the final value is borrowed rather than moved out of its container.
+///
+/// ```rust
+/// use avalanche::{component, tracked_keyed, updated, updated_keyed, View};
+/// use avalanche::tracked::{Gen, Tracked};
+///
+/// #[component]
+/// fn Example() -> View {
+///     let vec = Tracked::new(
+///         vec![
+///             Tracked::new(0u8, Gen::escape_hatch_new(false)),
+///             Tracked::new(1u8, Gen::escape_hatch_new(true)),
+///         ],
+///         Gen::escape_hatch_new(true),
+///     );
+///
+///     // vec was updated, but its zeroth element was not
+///     let first = tracked_keyed!(vec[0]);
+///     assert!(!updated!(first));
+///     assert!(updated_keyed!(vec[1]));
+///
+///     // ..
+///     # ().into()
+/// }
+/// ```
 #[macro_export]
 macro_rules! tracked_keyed {
     ($e:expr) => {
@@ -120,7 +215,8 @@ macro_rules! updated {
     };
 }
 
-/// Like [updated], but with the tracking performance properties of [tracked_keyed].
+/// Like [updated], but with the tracking performance properties of [tracked_keyed]: only the
+/// final [Tracked] value on the access path and any tracked index or argument expressions are considered.
 ///
 /// This is an experimental API.
 #[macro_export]
@@ -139,19 +235,31 @@ macro_rules! updated_keyed {
 ///
 /// Generations are ordered: a more recent generation is greater than an older one.
 pub(crate) struct InternalGen {
-    pub(crate) gen: u32,
+    pub(crate) gen: u64,
 }
 
-// TODO: support wrap edge cases
 impl InternalGen {
+    /// A generation that is never considered updated.
+    pub(crate) const NEVER_UPDATED: Self = InternalGen { gen: 0 };
+    /// A generation that is always considered updated, as no tree will ever reach it.
+    pub(crate) const ALWAYS_UPDATED: Self = InternalGen { gen: u64::MAX };
+
     /// Creates the `Gen` at which a component tree starts at.
     pub(crate) fn new() -> Self {
         InternalGen { gen: 1 }
     }
 
     /// Increments the generation.
+    ///
+    /// Generations are 64 bits wide so that they never wrap in practice: even at a billion renders
+    /// per second, a tree would take centuries to reach [ALWAYS_UPDATED](Self::ALWAYS_UPDATED).
+    /// Comparisons between generations can therefore remain plain integer comparisons.
     pub(crate) fn inc(&mut self) {
-        self.gen = self.gen.wrapping_add(1);
+        self.gen += 1;
+        assert!(
+            *self < Self::ALWAYS_UPDATED,
+            "avalanche generation counter overflowed"
+        );
     }
 }
 
@@ -182,10 +290,342 @@ impl<'a> Gen<'a> {
     /// not what you want, and is generally helpful only for manual component implementations
     /// that need a default not updated generation.
     pub fn escape_hatch_new(updated: bool) -> Self {
-        let gen = if updated { u32::MAX } else { 0 };
+        let gen = if updated {
+            InternalGen::ALWAYS_UPDATED
+        } else {
+            InternalGen::NEVER_UPDATED
+        };
+        gen.into()
+    }
+}
+
+/// Advances `current` to `gen` if `gen` is more recent.
+fn advance_gen(current: &mut Gen<'static>, gen: Gen) {
+    if *current < gen {
+        *current = gen.gen.into();
+    }
+}
+
+/// A stable identity for an entry of a [TrackedVec], assigned on insertion and kept
+/// across reorders. Implements `Display`, so it can be passed directly to [keyed](crate::keyed).
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Key(u64);
+
+impl Display for Key {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A vector that records a [Gen] for every element, as well as a separate one for its structure.
+///
+/// Mutating an element through [get_mut](TrackedVec::get_mut) only marks that element as updated,
+/// while inserting, removing, or reordering elements only marks the structure as updated.
+/// Every element is given a [Key] when it is inserted that stays the same when the vector is reordered.
+///
+/// `TrackedVec` is meant to be stored within the [store](crate::store) hook and modified within its
+/// setter's `update` callback:
+///
+/// ```rust
+/// use avalanche::{component, keyed, store, tracked, tracked_keyed, View};
+/// use avalanche::tracked::TrackedVec;
+///
+/// #[component]
+/// fn Items() -> View {
+///     let (items, update_items) = store(self, |gen| TrackedVec::from_values(["a", "b"], gen));
+///     let _add_item = move || update_items.update(|items, gen| {
+///         items.push("c", gen);
+///     });
+///
+///     // Only updated when the structure of `items` changes
+///     let _rows = tracked_keyed!(items.iter())
+///         .map(|(key, item)| keyed(self, key, || Item(self, name = tracked!(item))))
+///         .collect::<Vec<_>>();
+///     ().into()
+/// }
+///
+/// #[component]
+/// fn Item(name: &str) -> View {
+///     ().into()
+/// }
+/// ```
+#[derive(Clone)]
+pub struct TrackedVec<T> {
+    entries: Vec<(Key, Tracked<T>)>,
+    next_key: u64,
+    structure_gen: Gen<'static>,
+}
+
+impl<T> TrackedVec<T> {
+    /// Creates an empty vector whose structure was created at the given generation.
+    pub fn new(gen: Gen) -> Self {
         Self {
-            gen: InternalGen { gen },
-            phantom: PhantomData,
+            entries: Vec::new(),
+            next_key: 0,
+            structure_gen: gen.gen.into(),
+        }
+    }
+
+    /// Creates a vector containing `values`, all created at the given generation.
+    pub fn from_values(values: impl IntoIterator<Item = T>, gen: Gen) -> Self {
+        let mut vec = Self::new(gen);
+        for value in values {
+            vec.push(value, gen);
         }
+        vec
+    }
+
+    /// Returns the number of elements in the vector.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the vector contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the element at `index`, or `None` if out of bounds.
+    pub fn get(&self, index: usize) -> Option<&Tracked<T>> {
+        self.entries.get(index).map(|(_, value)| value)
+    }
+
+    /// Provides mutable access to the element at `index`, marking only that element as
+    /// updated at the given generation. Returns `None` if out of bounds.
+    pub fn get_mut(&mut self, index: usize, gen: Gen) -> Option<&mut T> {
+        self.entries
+            .get_mut(index)
+            .map(|(_, value)| value.mutate(gen))
+    }
+
+    /// Returns the key of the element at `index`, or `None` if out of bounds.
+    pub fn key(&self, index: usize) -> Option<Key> {
+        self.entries.get(index).map(|(key, _)| *key)
+    }
+
+    /// Returns the current index of the element with the given key, if it is still present.
+    pub fn position(&self, key: Key) -> Option<usize> {
+        self.entries.iter().position(|(k, _)| *k == key)
+    }
+
+    /// Appends `value` to the end of the vector and returns its key.
+    pub fn push(&mut self, value: T, gen: Gen) -> Key {
+        let key = self.next_key();
+        self.entries.push((key, Tracked::new(value, gen)));
+        advance_gen(&mut self.structure_gen, gen);
+        key
+    }
+
+    /// Inserts `value` at `index`, shifting all elements after it, and returns its key.
+    ///
+    /// # Panics
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, value: T, gen: Gen) -> Key {
+        let key = self.next_key();
+        self.entries.insert(index, (key, Tracked::new(value, gen)));
+        advance_gen(&mut self.structure_gen, gen);
+        key
+    }
+
+    /// Removes and returns the element at `index`, shifting all elements after it.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize, gen: Gen) -> T {
+        let (_, value) = self.entries.remove(index);
+        advance_gen(&mut self.structure_gen, gen);
+        value.__avalanche_internal_value
+    }
+
+    /// Swaps the elements at indices `a` and `b`. The elements keep their keys and generations.
+    ///
+    /// # Panics
+    /// Panics if `a` or `b` are out of bounds.
+    pub fn swap(&mut self, a: usize, b: usize, gen: Gen) {
+        self.entries.swap(a, b);
+        if a != b {
+            advance_gen(&mut self.structure_gen, gen);
+        }
+    }
+
+    /// Retains only the elements for which `f` returns `true`. The structure is only marked
+    /// as updated if an element was removed.
+    pub fn retain(&mut self, mut f: impl FnMut(&T) -> bool, gen: Gen) {
+        let len = self.entries.len();
+        self.entries
+            .retain(|(_, value)| f(&value.__avalanche_internal_value));
+        if self.entries.len() != len {
+            advance_gen(&mut self.structure_gen, gen);
+        }
+    }
+
+    /// Removes all elements from the vector.
+    pub fn clear(&mut self, gen: Gen) {
+        if !self.entries.is_empty() {
+            self.entries.clear();
+            advance_gen(&mut self.structure_gen, gen);
+        }
+    }
+
+    /// Returns an iterator over the keys and elements of the vector, tracked with
+    /// the generation of the vector's structure.
+    pub fn iter(&self) -> Tracked<Iter<'_, T>> {
+        Tracked::new(
+            Iter {
+                inner: self.entries.iter(),
+            },
+            self.structure_gen,
+        )
+    }
+
+    fn next_key(&mut self) -> Key {
+        let key = Key(self.next_key);
+        self.next_key += 1;
+        key
+    }
+}
+
+impl<T> Index<usize> for TrackedVec<T> {
+    type Output = Tracked<T>;
+
+    fn index(&self, index: usize) -> &Tracked<T> {
+        &self.entries[index].1
+    }
+}
+
+/// An iterator over the keys and elements of a [TrackedVec], created by [TrackedVec::iter].
+pub struct Iter<'a, T> {
+    inner: slice::Iter<'a, (Key, Tracked<T>)>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (Key, &'a Tracked<T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, value)| (*key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(key, value)| (*key, value))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+/// An ordered map that records a [Gen] for every value, as well as a separate one for its structure.
+///
+/// Mutating a value through [get_mut](TrackedMap::get_mut), or replacing the value of an existing
+/// key through [insert](TrackedMap::insert), only marks that value as updated, while adding or
+/// removing keys only marks the structure as updated. The map's keys serve as stable keys for
+/// [keyed](crate::keyed).
+#[derive(Clone)]
+pub struct TrackedMap<K, V> {
+    entries: BTreeMap<K, Tracked<V>>,
+    structure_gen: Gen<'static>,
+}
+
+impl<K: Ord, V> TrackedMap<K, V> {
+    /// Creates an empty map whose structure was created at the given generation.
+    pub fn new(gen: Gen) -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            structure_gen: gen.gen.into(),
+        }
+    }
+
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns `true` if the map contains a value for `key`.
+    pub fn contains_key<Q: Ord + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.entries.contains_key(key)
+    }
+
+    /// Returns the value corresponding to `key`.
+    pub fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&Tracked<V>>
+    where
+        K: Borrow<Q>,
+    {
+        self.entries.get(key)
+    }
+
+    /// Provides mutable access to the value corresponding to `key`, marking only that value as
+    /// updated at the given generation.
+    pub fn get_mut<Q: Ord + ?Sized>(&mut self, key: &Q, gen: Gen) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        self.entries.get_mut(key).map(|value| value.mutate(gen))
+    }
+
+    /// Inserts `value` at `key`, returning the previous value if there was one.
+    /// The structure is only marked as updated if `key` was not already present.
+    pub fn insert(&mut self, key: K, value: V, gen: Gen) -> Option<V> {
+        let old = self.entries.insert(key, Tracked::new(value, gen));
+        if old.is_none() {
+            advance_gen(&mut self.structure_gen, gen);
+        }
+        old.map(|old| old.__avalanche_internal_value)
+    }
+
+    /// Removes and returns the value at `key`, if present.
+    pub fn remove<Q: Ord + ?Sized>(&mut self, key: &Q, gen: Gen) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        let old = self.entries.remove(key);
+        if old.is_some() {
+            advance_gen(&mut self.structure_gen, gen);
+        }
+        old.map(|old| old.__avalanche_internal_value)
+    }
+
+    /// Retains only the entries for which `f` returns `true`. The structure is only marked
+    /// as updated if an entry was removed.
+    pub fn retain(&mut self, mut f: impl FnMut(&K, &V) -> bool, gen: Gen) {
+        let len = self.entries.len();
+        self.entries
+            .retain(|key, value| f(key, &value.__avalanche_internal_value));
+        if self.entries.len() != len {
+            advance_gen(&mut self.structure_gen, gen);
+        }
+    }
+
+    /// Removes all entries from the map.
+    pub fn clear(&mut self, gen: Gen) {
+        if !self.entries.is_empty() {
+            self.entries.clear();
+            advance_gen(&mut self.structure_gen, gen);
+        }
+    }
+
+    /// Returns an iterator over the entries of the map in key order, tracked with
+    /// the generation of the map's structure.
+    pub fn iter(&self) -> Tracked<btree_map::Iter<'_, K, Tracked<V>>> {
+        Tracked::new(self.entries.iter(), self.structure_gen)
+    }
+}
+
+impl<K: Ord + Borrow<Q>, Q: Ord + ?Sized, V> Index<&Q> for TrackedMap<K, V> {
+    type Output = Tracked<V>;
+
+    fn index(&self, key: &Q) -> &Tracked<V> {
+        &self.entries[key]
     }
 }
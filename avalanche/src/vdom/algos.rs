@@ -38,12 +38,11 @@ pub fn render_child<'a>(component: impl Component<'a>, context: &RenderContext)
                     key: context.shared.key.get().map(ToOwned::to_owned),
                     location: component.location().unwrap_or_default(),
                 };
+                let body_parent = vdom.children.get_mut(&context.body_parent_id).unwrap();
+                let depth = body_parent.depth + 1;
                 // Gets the `ComponentId` of the child if it existed previously,
                 // and generates a new one otherwise.
-                let body_child = vdom
-                    .children
-                    .get_mut(&context.body_parent_id)
-                    .unwrap()
+                let body_child = body_parent
                     .body_children
                     .entry(child_id)
                     .or_insert(BodyChild {
@@ -78,6 +77,7 @@ pub fn render_child<'a>(component: impl Component<'a>, context: &RenderContext)
                         });
                         VNode {
                             body_parent: Some(context.component_pos.component_id),
+                            depth,
                             body_children: FxHashMap::default(),
                             native_component,
                             state: FxHashMap::default(),
@@ -185,17 +185,20 @@ pub fn render_child<'a>(component: impl Component<'a>, context: &RenderContext)
                 Some(child_component_id)
             } else {
                 let mut state = ComponentState::default();
-                let vdom_gen = context.vdom.exec_mut(|vdom| {
+                let (vdom_gen, depth) = context.vdom.exec_mut(|vdom| {
                     let child_vnode = vdom.children.get_mut(&child_component_id).unwrap();
                     // swap state out of vnode to allow passing a mut VDom reference down the stack
                     std::mem::swap(&mut state, &mut child_vnode.state);
 
-                    // Before rendering mark all body children as unused
+                    // Before rendering mark all body children and state as unused
                     for (_, child) in child_vnode.body_children.iter_mut() {
                         child.used = false;
                     }
+                    for (_, entry) in state.iter_mut() {
+                        entry.used = false;
+                    }
 
-                    vdom.gen
+                    (vdom.gen, child_vnode.depth)
                 });
                 // wrap state to allow joint hook creation and state consumption
                 let shared_state = Shared::new(ComponentStateAccess::new(&mut state));
@@ -214,12 +217,14 @@ pub fn render_child<'a>(component: impl Component<'a>, context: &RenderContext)
                 let child_hook_context = HookContext {
                     gen: vdom_gen.into(),
                     state: &shared_state,
+                    depth,
                     component_pos: ComponentPos {
                         component_id: child_component_id,
                         vdom: context.component_pos.vdom,
                     },
                     bump: context.bump,
                     shared: context.shared,
+                    scope: None,
                 };
 
                 // Set key to None, as within the component, it becomes a body parent
@@ -243,6 +248,10 @@ pub fn render_child<'a>(component: impl Component<'a>, context: &RenderContext)
                             *used
                         });
 
+                    // Destroy state whose hooks were not called in the render, such as state
+                    // belonging to items removed from a list or to a hook behind a condition
+                    state.retain(|_, entry| entry.used);
+
                     // restore the vnode's state after swapping it out earlier
                     std::mem::swap(&mut state, &mut child_vnode.state);
                 });
@@ -423,6 +432,8 @@ pub(super) fn render_vdom<'a, C: DefaultComponent>(
     // Remove the vnodes marked for deletion
     vdom.remove_node(components_to_remove.into_inner());
 
+    vdom.renderer.flush();
+
     let vnode = vdom.children.get_mut(&ComponentId::new()).unwrap();
     let native_component = vnode.native_component.as_mut().unwrap();
 
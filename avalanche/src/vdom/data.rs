@@ -99,6 +99,8 @@ pub(super) struct BodyChild {
 pub(crate) struct VNode {
     /// The component in which the VNode's component was rendered in, if any.
     pub body_parent: Option<ComponentId>,
+    /// The number of body parents above the VNode.
+    pub(crate) depth: usize,
     /// The components rendered within the render function of the component, if any.
     pub(super) body_children: FxHashMap<ChildId, BodyChild>,
     /// The native information of the given component, if it is native.
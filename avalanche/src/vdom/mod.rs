@@ -1,5 +1,5 @@
 use std::mem::ManuallyDrop;
-use std::{any::Any, cell::RefCell, panic::Location, rc::Rc};
+use std::{cell::RefCell, rc::Rc};
 
 use rustc_hash::FxHashMap;
 
@@ -9,7 +9,7 @@ use crate::renderer::{DispatchNativeEvent, NativeEvent};
 use crate::shared::Shared;
 use crate::tracked::Gen;
 use crate::{
-    renderer::{NativeHandle, Renderer, Scheduler},
+    renderer::{NativeHandle, Priority, Renderer, Scheduler},
     tracked::InternalGen,
 };
 use crate::{Component, DefaultComponent, View};
@@ -25,9 +25,9 @@ pub(crate) mod state;
 pub use algos::render_child;
 use algos::render_vdom;
 use data::{ComponentId, NativeComponent, VDom, VNode};
-use state::SharedBox;
+use state::{StateEntry, StateKey};
 
-pub(crate) type ComponentState = FxHashMap<Location<'static>, SharedBox<dyn Any>>;
+pub(crate) type ComponentState = FxHashMap<StateKey, StateEntry>;
 
 /// Contains the data structures necessary to support the avalanche vdom abstraction. This struct
 /// should only be used by renderer implementation libraries.
@@ -51,9 +51,19 @@ impl Root {
     /// `unmount` method is called. Modifying those children before `unmount` is called will likely result
     /// in panics.
     pub fn new<'a, R: Renderer + 'static, S: Scheduler + 'static, C: DefaultComponent>(
+        native_handle: NativeHandle,
+        renderer: R,
+        scheduler: S,
+    ) -> Self {
+        Self::new_at_gen::<R, S, C>(native_handle, renderer, scheduler, InternalGen::new())
+    }
+
+    /// Like `new`, but starts rendering at the given generation instead of the first one.
+    pub(crate) fn new_at_gen<R: Renderer + 'static, S: Scheduler + 'static, C: DefaultComponent>(
         mut native_handle: NativeHandle,
         mut renderer: R,
         scheduler: S,
+        gen: InternalGen,
     ) -> Self {
         // Remove all the children of `native_handle`.
         renderer.truncate_children(&mut native_handle, 0);
@@ -64,6 +74,7 @@ impl Root {
         let mut children = FxHashMap::with_capacity_and_hasher(16, Default::default());
         let vnode = VNode {
             body_parent: None,
+            depth: 0,
             body_children,
             native_component: Some(NativeComponent {
                 native_handle,
@@ -82,7 +93,7 @@ impl Root {
             children,
             curr_component_id,
             renderer: Box::new(renderer),
-            gen: InternalGen::new(),
+            gen,
             update_vdom: render_vdom::<C>,
             bump: Bump::new(),
         };
@@ -113,11 +124,12 @@ impl Root {
                 // Clear children
                 vdom.renderer
                     .truncate_children(&mut native_root.native_handle, 0);
+                vdom.renderer.flush();
             });
         };
         if is_vdom_borrowed {
             self.scheduler.exec_mut(|scheduler| {
-                scheduler.schedule_on_ui_thread(Box::new(exec_unmount));
+                scheduler.schedule_on_ui_thread(Box::new(exec_unmount), Priority::Immediate);
             })
         } else {
             exec_unmount();
@@ -56,15 +56,39 @@ impl<'a> ComponentStateAccess<'a> {
         Self { inner }
     }
 
+    /// Gets the state at `key`, inserting `value` if it is not present, and marks it as used.
     pub fn get_or_insert_with(
         &mut self,
-        key: Location<'static>,
+        key: StateKey,
         value: impl FnOnce() -> SharedBox<dyn Any>,
     ) -> &'a dyn Any {
-        let elem = self.inner.entry(key).or_insert_with(value);
+        let elem = self.inner.entry(key).or_insert_with(|| StateEntry {
+            value: value(),
+            used: true,
+        });
+        elem.used = true;
 
         // safety: The box cannot be destroyed or mutably dereferenced until the end of the lifetime
         // 'a, as per the guarantees on inner.
-        unsafe { elem.get_ref() }
+        unsafe { elem.value.get_ref() }
     }
 }
+
+/// Identifies a piece of hook state within a component.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub(crate) struct StateKey {
+    /// The location of the hook call.
+    pub location: Location<'static>,
+    /// The keys given to the hook's context by `HookContext::with_key`, if any.
+    pub scope: Option<String>,
+    /// The key provided to keyed hooks, if any.
+    pub key: Option<String>,
+}
+
+/// A piece of hook state stored within a component.
+pub(crate) struct StateEntry {
+    pub value: SharedBox<dyn Any>,
+    /// Whether the state was accessed during the current render of its component.
+    /// State that is not accessed during a render is destroyed after it.
+    pub used: bool,
+}